@@ -81,12 +81,18 @@ async fn main() -> Result<(), what3words_api::Error> {
         Err(err) => println!("{:?}", err),
     };
     // ------ HELPER FUNCTIONS ------
-    let is_valid_3wa: bool = w3w.is_valid_3wa("filled.count.soap").await;
-    println!("is_valid_3wa [1]: {}", is_valid_3wa);
-    let is_valid_3wa: bool = w3w.is_valid_3wa("filled.count.").await;
-    println!("is_valid_3wa [2]: {}", is_valid_3wa);
-    let is_valid_3wa: bool = w3w.is_valid_3wa("rust.is.cool").await;
-    println!("is_valid_3wa [3]: {}", is_valid_3wa);
+    match w3w.is_valid_3wa("filled.count.soap").await {
+        Ok(is_valid_3wa) => println!("is_valid_3wa [1]: {}", is_valid_3wa),
+        Err(error) => println!("is_valid_3wa [1] could not be checked: {:?}", error),
+    }
+    match w3w.is_valid_3wa("filled.count.").await {
+        Ok(is_valid_3wa) => println!("is_valid_3wa [2]: {}", is_valid_3wa),
+        Err(error) => println!("is_valid_3wa [2] could not be checked: {:?}", error),
+    }
+    match w3w.is_valid_3wa("rust.is.cool").await {
+        Ok(is_valid_3wa) => println!("is_valid_3wa [3]: {}", is_valid_3wa),
+        Err(error) => println!("is_valid_3wa [3] could not be checked: {:?}", error),
+    }
 
     Ok(())
 }
@@ -71,12 +71,18 @@ fn main() -> Result<(), Error> {
         Err(err) => println!("{:?}", err),
     };
     // ------ HELPER FUNCTIONS ------
-    let is_valid_3wa: bool = w3w.is_valid_3wa("filled.count.soap");
-    println!("is_valid_3wa [1]: {}", is_valid_3wa);
-    let is_valid_3wa: bool = w3w.is_valid_3wa("filled.count");
-    println!("is_valid_3wa [2]: {}", is_valid_3wa);
-    let is_valid_3wa: bool = w3w.is_valid_3wa("rust.is.cool");
-    println!("is_valid_3wa [3]: {}", is_valid_3wa);
+    match w3w.is_valid_3wa("filled.count.soap") {
+        Ok(is_valid_3wa) => println!("is_valid_3wa [1]: {}", is_valid_3wa),
+        Err(error) => println!("is_valid_3wa [1] could not be checked: {:?}", error),
+    }
+    match w3w.is_valid_3wa("filled.count") {
+        Ok(is_valid_3wa) => println!("is_valid_3wa [2]: {}", is_valid_3wa),
+        Err(error) => println!("is_valid_3wa [2] could not be checked: {:?}", error),
+    }
+    match w3w.is_valid_3wa("rust.is.cool") {
+        Ok(is_valid_3wa) => println!("is_valid_3wa [3]: {}", is_valid_3wa),
+        Err(error) => println!("is_valid_3wa [3] could not be checked: {:?}", error),
+    }
     let did_you_mean: bool = w3w.did_you_mean("filled count soap");
     println!("did_you_mean [1]: {}", did_you_mean);
     let did_you_mean: bool = w3w.did_you_mean("filled-count-soap");
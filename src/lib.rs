@@ -1,13 +1,21 @@
 pub use self::models::{
-    autosuggest::{Autosuggest, AutosuggestResult, AutosuggestSelection, Suggestion},
+    autosuggest::{
+        Autosuggest, AutosuggestInputType, AutosuggestResult, AutosuggestSelection, InputType,
+        Suggestion,
+    },
+    country::CountryCode,
+    empty::Empty,
     gridsection::{BoundingBox, GridSection, GridSectionGeoJson},
     language::{AvailableLanguages, Language},
     location::{
-        Address, AddressGeoJson, Circle, ConvertTo3wa, ConvertToCoordinates, Coordinates, Polygon,
-        Square,
+        Address, AddressGeoJson, AddressProperties, Circle, ConvertTo3wa, ConvertToCoordinates,
+        Coordinates, Polygon, RoundedCoordinates, Square,
     },
 };
-pub use self::service::{Error, What3words};
+pub use self::service::{
+    ApiErrorCode, BackoffStrategy, Error, ErrorKind, ExponentialBackoff, HasKey, NoKey, What3words,
+    What3wordsBuilder,
+};
 
 mod models;
 mod service;
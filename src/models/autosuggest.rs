@@ -1,20 +1,93 @@
+use super::country::CountryCode;
 use super::gridsection::BoundingBox;
-use super::location::{Circle, Coordinates, Polygon, Square};
-use crate::service::{Error, ToHashMap, Validator};
-use serde::Deserialize;
-use std::{collections::HashMap, fmt};
+use super::location::{Circle, ConvertToCoordinates, Coordinates, Polygon, Square};
+use crate::service::{format_with_precision, Error, ToHashMap, Validator};
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, collections::HashMap, fmt, str::FromStr};
 
-#[derive(Debug, Clone)]
+/// The kinds of input autosuggest accepts, telling the API which speech recogniser (if any)
+/// produced it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum InputType {
+    Text,
+    VoconHybrid,
+    NmdpAsr,
+    GenericVoice,
+    Speechmatics,
+    MihupAsr,
+    Mawdoo3,
+    OcrSdk,
+}
+
+/// Alias of [`InputType`] for callers who prefer the fully qualified name.
+pub type AutosuggestInputType = InputType;
+
+impl InputType {
+    const ALL: [InputType; 8] = [
+        InputType::Text,
+        InputType::VoconHybrid,
+        InputType::NmdpAsr,
+        InputType::GenericVoice,
+        InputType::Speechmatics,
+        InputType::MihupAsr,
+        InputType::Mawdoo3,
+        InputType::OcrSdk,
+    ];
+
+    /// The exact `input-type` token the API expects.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InputType::Text => "text",
+            InputType::VoconHybrid => "vocon-hybrid",
+            InputType::NmdpAsr => "nmdp-asr",
+            InputType::GenericVoice => "generic-voice",
+            InputType::Speechmatics => "speechmatics",
+            InputType::MihupAsr => "mihup-asr",
+            InputType::Mawdoo3 => "mawdoo3",
+            InputType::OcrSdk => "ocr-sdk",
+        }
+    }
+}
+
+impl fmt::Display for InputType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parses an API token such as `"vocon-hybrid"`, rejecting anything the API doesn't document.
+impl FromStr for InputType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        InputType::ALL
+            .into_iter()
+            .find(|input_type| input_type.as_str() == s)
+            .ok_or(Error::InvalidParameter("Unknown autosuggest input type."))
+    }
+}
+
+impl TryFrom<&str> for InputType {
+    type Error = Error;
+
+    fn try_from(input_type: &str) -> Result<Self, Self::Error> {
+        input_type.parse()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct Autosuggest {
     input: Option<String>,
-    n_results: Option<String>,
-    focus: Option<String>,
-    n_focus_result: Option<String>,
+    voice_input: Option<serde_json::Value>,
+    n_results: Option<u8>,
+    focus: Option<Coordinates>,
+    n_focus_result: Option<u8>,
     clip_to_country: Option<String>,
     clip_to_bounding_box: Option<BoundingBox>,
     clip_to_circle: Option<Circle>,
     clip_to_polygon: Option<Polygon>,
-    input_type: Option<String>,
+    input_type: Option<InputType>,
     language: Option<String>,
     prefer_land: Option<bool>,
     locale: Option<String>,
@@ -22,43 +95,61 @@ pub struct Autosuggest {
 
 impl Validator for Autosuggest {
     fn validate(&self) -> std::result::Result<(), Error> {
-        if let Some(ref clip_to_polygon) = &self.clip_to_polygon {
-            clip_to_polygon.validate()?;
+        match self.validate_all().into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(()),
         }
-        Ok(())
     }
 }
 
 impl ToHashMap for Autosuggest {
     fn to_hash_map<'a>(&self) -> Result<HashMap<&'a str, String>, Error> {
+        self.to_hash_map_with_precision(None)
+    }
+
+    fn to_hash_map_with_precision<'a>(
+        &self,
+        precision: Option<usize>,
+    ) -> Result<HashMap<&'a str, String>, Error> {
         self.validate()?;
         let mut map = HashMap::new();
         if let Some(ref input) = &self.input {
             map.insert("input", input.into());
         }
-        if let Some(ref n_results) = &self.n_results {
-            map.insert("n-results", n_results.into());
+        if let Some(n_results) = self.n_results {
+            map.insert("n-results", n_results.to_string());
         }
         if let Some(ref focus) = &self.focus {
-            map.insert("focus", focus.into());
+            map.insert("focus", format_with_precision(focus, precision));
         }
-        if let Some(ref n_focus_result) = &self.n_focus_result {
-            map.insert("n-focus-result", n_focus_result.into());
+        if let Some(n_focus_result) = self.n_focus_result {
+            map.insert("n-focus-result", n_focus_result.to_string());
         }
         if let Some(ref clip_to_country) = &self.clip_to_country {
             map.insert("clip-to-country", clip_to_country.into());
         }
         if let Some(ref clip_to_bounding_box) = &self.clip_to_bounding_box {
-            map.insert("clip-to-bounding-box", clip_to_bounding_box.to_string());
+            map.insert(
+                "clip-to-bounding-box",
+                format_with_precision(clip_to_bounding_box, precision),
+            );
         }
         if let Some(ref clip_to_circle) = &self.clip_to_circle {
-            map.insert("clip-to-circle", clip_to_circle.to_string());
+            map.insert(
+                "clip-to-circle",
+                format_with_precision(clip_to_circle, precision),
+            );
         }
         if let Some(ref clip_to_polygon) = &self.clip_to_polygon {
-            map.insert("clip-to-polygon", clip_to_polygon.to_string());
+            map.insert(
+                "clip-to-polygon",
+                format_with_precision(clip_to_polygon, precision),
+            );
         }
         if let Some(ref input_type) = &self.input_type {
-            map.insert("input-type", input_type.into());
+            map.insert("input-type", input_type.to_string());
+        } else if self.voice_input.is_some() {
+            map.insert("input-type", InputType::GenericVoice.to_string());
         }
         if let Some(ref language) = &self.language {
             map.insert("language", language.into());
@@ -75,8 +166,14 @@ impl ToHashMap for Autosuggest {
 
 impl Autosuggest {
     pub fn new(input: impl Into<String>) -> Self {
+        Self::builder().input(input)
+    }
+
+    /// Starts an `Autosuggest` without an input, to be set later with [`Autosuggest::input`].
+    pub fn builder() -> Self {
         Self {
-            input: Some(input.into()),
+            input: None,
+            voice_input: None,
             n_results: None,
             focus: None,
             n_focus_result: None,
@@ -90,25 +187,106 @@ impl Autosuggest {
             locale: None,
         }
     }
-    pub fn n_results(mut self, n_results: impl Into<String>) -> Self {
-        self.n_results = Some(n_results.into());
+
+    /// Returns every validation problem rather than only the first, e.g. to show them all at
+    /// once in a form. Empty when the request is valid.
+    pub fn validate_all(&self) -> Vec<Error> {
+        let mut errors = Vec::new();
+        if self.input.is_none() && self.voice_input.is_none() {
+            errors.push(Error::InvalidParameter("An autosuggest input is required."));
+        }
+        if self.voice_input.is_some() && self.input_type == Some(InputType::Text) {
+            errors.push(Error::InvalidParameter(
+                "A voice input needs a voice input-type, such as generic-voice.",
+            ));
+        }
+        if let Some(n_results) = self.n_results {
+            if !(1..=100).contains(&n_results) {
+                errors.push(Error::InvalidParameter(
+                    "n-results must be between 1 and 100.",
+                ));
+            }
+        }
+        if let Some(n_focus_result) = self.n_focus_result {
+            if !(1..=100).contains(&n_focus_result) {
+                errors.push(Error::InvalidParameter(
+                    "n-focus-result must be a number between 1 and 100.",
+                ));
+            } else if self
+                .n_results
+                .is_some_and(|n_results| n_focus_result > n_results)
+            {
+                errors.push(Error::InvalidParameter(
+                    "n-focus-result must be a number no greater than n-results.",
+                ));
+            }
+        }
+        if let Some(ref clip_to_polygon) = &self.clip_to_polygon {
+            errors.extend(clip_to_polygon.validate().err());
+        }
+        errors
+    }
+
+    /// Sets or replaces the input, leaving every other option as it is, so a configured template
+    /// can be cloned for each new query.
+    pub fn input(mut self, input: impl Into<String>) -> Self {
+        self.input = Some(input.into());
+        self
+    }
+
+    /// Sets a structured transcript from a speech recogniser, which is POSTed as the request
+    /// body instead of sending a plain `input`. Without an explicit `input_type`, the request is
+    /// sent as `generic-voice`.
+    pub fn voice_input(mut self, voice_input: serde_json::Value) -> Self {
+        self.voice_input = Some(voice_input);
+        self
+    }
+
+    pub(crate) fn voice_input_body(&self) -> Option<&serde_json::Value> {
+        self.voice_input.as_ref()
+    }
+
+    /// Returns the parameters as a percent-encoded `key=value&...` string, sorted by key, for use
+    /// with another HTTP client. The API key is not included.
+    pub fn to_query_string(&self) -> Result<String, Error> {
+        let mut params = self.to_hash_map()?.into_iter().collect::<Vec<_>>();
+        params.sort();
+        Ok(serde_urlencoded::to_string(params).expect("string pairs always URL-encode"))
+    }
+
+    pub(crate) fn clips_to_polygon(&self) -> bool {
+        self.clip_to_polygon.is_some()
+    }
+
+    pub(crate) fn raw_input(&self) -> &str {
+        self.input.as_deref().unwrap_or_default()
+    }
+
+    pub fn n_results(mut self, n_results: u8) -> Self {
+        self.n_results = Some(n_results);
         self
     }
 
     pub fn focus(mut self, focus: &Coordinates) -> Self {
-        self.focus = Some(focus.to_string());
+        self.focus = Some(focus.clone());
         self
     }
 
-    pub fn n_focus_result(mut self, n_focus_result: impl Into<String>) -> Self {
-        self.n_focus_result = Some(n_focus_result.into());
+    pub fn focus_on_box(self, bounding_box: &BoundingBox) -> Self {
+        self.focus(&bounding_box.center())
+    }
+
+    /// Must be between 1 and 100 and, when `n_results` is set, no greater than it; checked by
+    /// validation before any request is sent.
+    pub fn n_focus_result(mut self, n_focus_result: u8) -> Self {
+        self.n_focus_result = Some(n_focus_result);
         self
     }
 
-    pub fn clip_to_country(mut self, clip_to_country: &[impl Into<String> + Clone]) -> Self {
+    pub fn clip_to_country(mut self, clip_to_country: &[CountryCode]) -> Self {
         let countries = clip_to_country
             .iter()
-            .map(|c| c.clone().into())
+            .map(|country| country.to_string())
             .collect::<Vec<String>>()
             .join(",");
         self.clip_to_country = Some(countries);
@@ -130,11 +308,17 @@ impl Autosuggest {
         self
     }
 
-    pub fn input_type(mut self, input_type: impl Into<String>) -> Self {
-        self.input_type = Some(input_type.into());
+    pub fn input_type(mut self, input_type: InputType) -> Self {
+        self.input_type = Some(input_type);
         self
     }
 
+    /// Sets the input type from its API token, e.g. `"vocon-hybrid"`, as accepted before
+    /// [`InputType`] was introduced; unknown tokens are rejected.
+    pub fn input_type_str(self, input_type: &str) -> Result<Self, Error> {
+        Ok(self.input_type(input_type.parse()?))
+    }
+
     pub fn language(mut self, language: impl Into<String>) -> Self {
         self.language = Some(language.into());
         self
@@ -149,6 +333,17 @@ impl Autosuggest {
         self.locale = Some(locale.into());
         self
     }
+
+    /// Returns the query parameters as key-sorted pairs, e.g. for request signing or debugging.
+    pub fn to_sorted_params(&self) -> Result<Vec<(String, String)>, Error> {
+        let mut params = self
+            .to_hash_map()?
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect::<Vec<_>>();
+        params.sort();
+        Ok(params)
+    }
 }
 
 impl fmt::Display for Autosuggest {
@@ -157,7 +352,7 @@ impl fmt::Display for Autosuggest {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AutosuggestSelection {
     raw_input: Option<String>,
     options: Option<Autosuggest>,
@@ -166,6 +361,13 @@ pub struct AutosuggestSelection {
 
 impl ToHashMap for AutosuggestSelection {
     fn to_hash_map<'a>(&self) -> Result<HashMap<&'a str, String>, Error> {
+        self.to_hash_map_with_precision(None)
+    }
+
+    fn to_hash_map_with_precision<'a>(
+        &self,
+        precision: Option<usize>,
+    ) -> Result<HashMap<&'a str, String>, Error> {
         let mut map = HashMap::new();
         if let Some(ref raw_input) = &self.raw_input {
             map.insert("raw-input", raw_input.clone());
@@ -175,7 +377,7 @@ impl ToHashMap for AutosuggestSelection {
             map.insert("selection", suggestion.words.clone());
         }
         if let Some(ref options) = &self.options {
-            let options_map = options.to_hash_map()?;
+            let options_map = options.to_hash_map_with_precision(precision)?;
             map.extend(options_map);
         }
         Ok(map)
@@ -196,7 +398,7 @@ impl AutosuggestSelection {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Suggestion {
     pub country: String,
     #[serde(rename = "nearestPlace")]
@@ -205,17 +407,144 @@ pub struct Suggestion {
     pub rank: u32,
     pub language: String,
     #[serde(rename = "distanceToFocusKm")]
-    pub distance_to_focus_km: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance_to_focus_km: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub square: Option<Square>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub coordinates: Option<Coordinates>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub map: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+/// Formats as `words [rank n] — nearest place`, for log messages.
+impl fmt::Display for Suggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} [rank {}] — {}",
+            self.words, self.rank, self.nearest_place
+        )
+    }
+}
+
+/// Suggestions are equal when they name the same 3 word address, whatever their rank.
+impl PartialEq for Suggestion {
+    fn eq(&self, other: &Self) -> bool {
+        self.words == other.words
+    }
+}
+
+impl Eq for Suggestion {}
+
+/// Orders by `words`, consistent with `==`, so sorting and deduplicating a merged list keeps one
+/// suggestion per 3 word address. Use [`Suggestion::by_rank`] to sort by rank instead.
+impl Ord for Suggestion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.words.cmp(&other.words)
+    }
+}
+
+impl PartialOrd for Suggestion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Suggestion {
+    /// Comparator for `sort_by` that puts the top-ranked suggestion first, breaking ties on
+    /// `words`.
+    pub fn by_rank(a: &Suggestion, b: &Suggestion) -> Ordering {
+        a.rank.cmp(&b.rank).then_with(|| a.words.cmp(&b.words))
+    }
+
+    /// Comparator for `sort_by` that puts the suggestion closest to the focus first; those
+    /// without a `distance_to_focus_km` sort last.
+    pub fn by_distance(a: &Suggestion, b: &Suggestion) -> Ordering {
+        match (a.distance_to_focus_km, b.distance_to_focus_km) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+
+    pub fn to_coordinates_request_with_language(&self) -> ConvertToCoordinates {
+        ConvertToCoordinates::new(&self.words).locale(&self.language)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
 pub struct AutosuggestResult {
     pub suggestions: Vec<Suggestion>,
 }
 
+impl AutosuggestResult {
+    /// Returns the smallest box enclosing every suggestion that carries coordinates, or `None`
+    /// if none do.
+    pub fn bounds(&self) -> Option<BoundingBox> {
+        let mut coordinates = self
+            .suggestions
+            .iter()
+            .filter_map(|suggestion| suggestion.coordinates.as_ref());
+        let first = coordinates.next()?;
+        let (sw_lat, sw_lng, ne_lat, ne_lng) = coordinates.fold(
+            (first.lat, first.lng, first.lat, first.lng),
+            |(sw_lat, sw_lng, ne_lat, ne_lng), c| {
+                (
+                    sw_lat.min(c.lat),
+                    sw_lng.min(c.lng),
+                    ne_lat.max(c.lat),
+                    ne_lng.max(c.lng),
+                )
+            },
+        );
+        Some(BoundingBox::new(sw_lat, sw_lng, ne_lat, ne_lng))
+    }
+
+    /// Returns `true` if any suggestion, not only the top-ranked one, is exactly `words`.
+    pub fn contains_exact(&self, words: &str) -> bool {
+        self.suggestions
+            .iter()
+            .any(|suggestion| suggestion.words == words)
+    }
+
+    /// Returns the top-ranked suggestion, or `None` if there are no suggestions.
+    pub fn best_suggestion(&self) -> Option<&Suggestion> {
+        self.suggestions
+            .iter()
+            .min_by_key(|suggestion| suggestion.rank)
+    }
+
+    /// Returns the suggestion closest to the focus, or `None` if no suggestion has a
+    /// `distance_to_focus_km`.
+    pub fn focused_suggestion(&self) -> Option<&Suggestion> {
+        self.suggestions
+            .iter()
+            .filter_map(|suggestion| Some((suggestion.distance_to_focus_km?, suggestion)))
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, suggestion)| suggestion)
+    }
+}
+
+impl IntoIterator for AutosuggestResult {
+    type Item = Suggestion;
+    type IntoIter = std::vec::IntoIter<Suggestion>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.suggestions.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a AutosuggestResult {
+    type Item = &'a Suggestion;
+    type IntoIter = std::slice::Iter<'a, Suggestion>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.suggestions.iter()
+    }
+}
+
 #[cfg(test)]
 mod autosuggest_tests {
     use super::*;
@@ -223,13 +552,13 @@ mod autosuggest_tests {
     #[test]
     fn test_autosuggest_display() {
         let autosuggest = Autosuggest::new("test input")
-            .n_results("5")
+            .n_results(5)
             .focus(&Coordinates {
                 lat: 51.521251,
                 lng: -0.203586,
             })
-            .n_focus_result("3")
-            .clip_to_country(&["GB"])
+            .n_focus_result(3)
+            .clip_to_country(&[CountryCode::new("GB").unwrap()])
             .clip_to_bounding_box(&BoundingBox::new(
                 51.521251, -0.203586, 51.521251, -0.203586,
             ))
@@ -239,27 +568,27 @@ mod autosuggest_tests {
                 Coordinates::new(51.521251, -0.203586),
                 Coordinates::new(51.521251, -0.203581),
             ]))
-            .input_type("text")
+            .input_type(InputType::Text)
             .language("en")
             .prefer_land(true)
             .locale("en-GB");
 
         assert_eq!(
                     format!("{}", autosuggest),
-                    "Autosuggest { input: Some(\"test input\"), n_results: Some(\"5\"), focus: Some(\"51.521251,-0.203586\"), n_focus_result: Some(\"3\"), clip_to_country: Some(\"GB\"), clip_to_bounding_box: Some(BoundingBox { southwest: Coordinates { lat: 51.521251, lng: -0.203586 }, northeast: Coordinates { lat: 51.521251, lng: -0.203586 } }), clip_to_circle: Some(Circle { lat: 51.521251, lng: -0.203586, radius: 1000 }), clip_to_polygon: Some(Polygon { coordinates: [Coordinates { lat: 51.521251, lng: -0.203586 }, Coordinates { lat: 51.521251, lng: -0.203586 }, Coordinates { lat: 51.521251, lng: -0.203581 }] }), input_type: Some(\"text\"), language: Some(\"en\"), prefer_land: Some(true), locale: Some(\"en-GB\") }"
+                    "Autosuggest { input: Some(\"test input\"), voice_input: None, n_results: Some(5), focus: Some(Coordinates { lat: 51.521251, lng: -0.203586 }), n_focus_result: Some(3), clip_to_country: Some(\"GB\"), clip_to_bounding_box: Some(BoundingBox { southwest: Coordinates { lat: 51.521251, lng: -0.203586 }, northeast: Coordinates { lat: 51.521251, lng: -0.203586 } }), clip_to_circle: Some(Circle { lat: 51.521251, lng: -0.203586, radius: 1000.0 }), clip_to_polygon: Some(Polygon { coordinates: [Coordinates { lat: 51.521251, lng: -0.203586 }, Coordinates { lat: 51.521251, lng: -0.203586 }, Coordinates { lat: 51.521251, lng: -0.203581 }] }), input_type: Some(Text), language: Some(\"en\"), prefer_land: Some(true), locale: Some(\"en-GB\") }"
                 );
     }
 
     #[test]
     fn test_autosuggest_to_hash_map() {
         let autosuggest = Autosuggest::new("test input")
-            .n_results("5")
+            .n_results(5)
             .focus(&Coordinates {
                 lat: 51.521251,
                 lng: -0.203586,
             })
-            .n_focus_result("3")
-            .clip_to_country(&["GB"])
+            .n_focus_result(3)
+            .clip_to_country(&[CountryCode::new("GB").unwrap()])
             .clip_to_bounding_box(&BoundingBox::new(
                 51.521251, -0.203586, 51.521251, -0.203586,
             ))
@@ -269,7 +598,7 @@ mod autosuggest_tests {
                 Coordinates::new(51.521251, -0.203586),
                 Coordinates::new(51.521251, -0.203586),
             ]))
-            .input_type("text")
+            .input_type(InputType::Text)
             .language("en")
             .prefer_land(true)
             .locale("en-GB");
@@ -299,6 +628,43 @@ mod autosuggest_tests {
         }
     }
 
+    #[test]
+    fn test_autosuggest_to_hash_map_with_precision() {
+        let autosuggest = Autosuggest::new("test input")
+            .focus(&Coordinates::new(51.521251, -0.203586))
+            .clip_to_bounding_box(&BoundingBox::new(51.5, -0.25, 51.55, -0.15))
+            .clip_to_circle(&Circle::new(51.521251, -0.203586, 10))
+            .clip_to_polygon(&Polygon::new(&[
+                Coordinates::new(51.521251, -0.203586),
+                Coordinates::new(51.521261, -0.203586),
+                Coordinates::new(51.521261, -0.203575),
+                Coordinates::new(51.521251, -0.203586),
+            ]));
+
+        let map = autosuggest.to_hash_map_with_precision(Some(3)).unwrap();
+        assert_eq!(map.get("focus"), Some(&"51.521,-0.204".to_string()));
+        assert_eq!(
+            map.get("clip-to-bounding-box"),
+            Some(&"51.5,-0.25,51.55,-0.15".to_string())
+        );
+        assert_eq!(
+            map.get("clip-to-circle"),
+            Some(&"51.521,-0.204,10".to_string())
+        );
+        assert_eq!(
+            map.get("clip-to-polygon"),
+            Some(&"51.521,-0.204,51.521,-0.204,51.521,-0.204,51.521,-0.204".to_string())
+        );
+    }
+
+    #[test]
+    fn test_autosuggest_focus_on_box() {
+        let autosuggest =
+            Autosuggest::new("test input").focus_on_box(&BoundingBox::new(51.0, -1.0, 52.0, 1.0));
+        let map = autosuggest.to_hash_map().unwrap();
+        assert_eq!(map.get("focus"), Some(&"51.5,0".to_string()));
+    }
+
     #[test]
     fn test_autosuggest_validator() {
         // Test valid polygon
@@ -317,6 +683,61 @@ mod autosuggest_tests {
         assert!(invalid_autosuggest.validate().is_err());
     }
 
+    #[test]
+    fn test_autosuggest_builder() {
+        let autosuggest = Autosuggest::builder().n_results(3);
+        assert!(matches!(
+            autosuggest.to_hash_map(),
+            Err(Error::InvalidParameter(_))
+        ));
+
+        let map = autosuggest.input("filled.count.so").to_hash_map().unwrap();
+        assert_eq!(map.get("input"), Some(&"filled.count.so".to_string()));
+        assert_eq!(map.get("n-results"), Some(&"3".to_string()));
+    }
+
+    #[test]
+    fn test_autosuggest_input_replaces_template_input() {
+        let template = Autosuggest::new("filled.count.so")
+            .focus(&Coordinates::new(51.521251, -0.203586))
+            .clip_to_country(&[CountryCode::new("GB").unwrap()]);
+        let map = template
+            .clone()
+            .input("index.home.ra")
+            .to_hash_map()
+            .unwrap();
+        assert_eq!(map.get("input"), Some(&"index.home.ra".to_string()));
+        assert_eq!(map.get("focus"), Some(&"51.521251,-0.203586".to_string()));
+        assert_eq!(map.get("clip-to-country"), Some(&"GB".to_string()));
+        assert_eq!(
+            template.to_hash_map().unwrap().get("input"),
+            Some(&"filled.count.so".to_string())
+        );
+    }
+
+    #[test]
+    fn test_autosuggest_to_sorted_params() {
+        let autosuggest = Autosuggest::new("filled.count.so")
+            .n_results(5)
+            .language("en")
+            .focus(&Coordinates::new(51.521251, -0.203586))
+            .clip_to_country(&[CountryCode::new("GB").unwrap()]);
+
+        let params = autosuggest.to_sorted_params().unwrap();
+        let keys = params
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec!["clip-to-country", "focus", "input", "language", "n-results"]
+        );
+        assert_eq!(
+            params[2],
+            ("input".to_string(), "filled.count.so".to_string())
+        );
+    }
+
     #[test]
     fn test_autosuggest_empty() {
         let autosuggest = Autosuggest::new("");
@@ -358,14 +779,14 @@ mod autosuggest_tests {
             words: "index.home.raft".to_string(),
             rank: 1,
             language: "en".to_string(),
-            distance_to_focus_km: Some(10),
+            distance_to_focus_km: Some(10.0),
             square: None,
             coordinates: None,
             map: None,
         };
 
         let autosuggest = Autosuggest::new("test input")
-            .n_results("5")
+            .n_results(5)
             .focus(&Coordinates {
                 lat: 51.521251,
                 lng: -0.203586,
@@ -382,4 +803,494 @@ mod autosuggest_tests {
             assert_eq!(map.get("focus"), Some(&"51.521251,-0.203586".to_string()));
         }
     }
+
+    #[test]
+    fn test_suggestion_to_coordinates_request_with_language() {
+        let suggestion = Suggestion {
+            country: "MN".to_string(),
+            nearest_place: "Ulaanbaatar".to_string(),
+            words: "seruuhen.zemseg.dagaldah".to_string(),
+            rank: 1,
+            language: "mn".to_string(),
+            distance_to_focus_km: None,
+            square: None,
+            coordinates: None,
+            map: None,
+        };
+
+        let map = suggestion
+            .to_coordinates_request_with_language()
+            .to_hash_map()
+            .unwrap();
+        assert_eq!(
+            map.get("words"),
+            Some(&"seruuhen.zemseg.dagaldah".to_string())
+        );
+        assert_eq!(map.get("locale"), Some(&"mn".to_string()));
+    }
+
+    #[test]
+    fn test_autosuggest_result_bounds() {
+        let suggestion = |words: &str, coordinates: Option<Coordinates>| Suggestion {
+            country: "GB".to_string(),
+            nearest_place: "London".to_string(),
+            words: words.to_string(),
+            rank: 1,
+            language: "en".to_string(),
+            distance_to_focus_km: None,
+            square: None,
+            coordinates,
+            map: None,
+        };
+
+        let result = AutosuggestResult {
+            suggestions: vec![
+                suggestion(
+                    "filled.count.soap",
+                    Some(Coordinates::new(51.521251, -0.203586)),
+                ),
+                suggestion("filled.count.soaps", None),
+                suggestion(
+                    "index.home.raft",
+                    Some(Coordinates::new(51.520847, -0.195521)),
+                ),
+            ],
+        };
+        assert_eq!(
+            result.bounds().unwrap().to_string(),
+            "51.520847,-0.203586,51.521251,-0.195521"
+        );
+
+        let result = AutosuggestResult {
+            suggestions: vec![suggestion("filled.count.soap", None)],
+        };
+        assert!(result.bounds().is_none());
+    }
+
+    #[test]
+    fn test_autosuggest_result_contains_exact() {
+        let suggestion = |words: &str, rank: u32| Suggestion {
+            country: "GB".to_string(),
+            nearest_place: "London".to_string(),
+            words: words.to_string(),
+            rank,
+            language: "en".to_string(),
+            distance_to_focus_km: None,
+            square: None,
+            coordinates: None,
+            map: None,
+        };
+
+        let result = AutosuggestResult {
+            suggestions: vec![
+                suggestion("filled.count.soaps", 1),
+                suggestion("filled.count.soap", 2),
+                suggestion("filled.counts.soap", 3),
+            ],
+        };
+        assert!(result.contains_exact("filled.count.soap"));
+        assert!(!result.contains_exact("filled.count"));
+        assert!(!result.contains_exact("FILLED.COUNT.SOAP"));
+    }
+
+    #[test]
+    fn test_autosuggest_input_type() {
+        let cases = [
+            (InputType::Text, "text"),
+            (InputType::VoconHybrid, "vocon-hybrid"),
+            (InputType::NmdpAsr, "nmdp-asr"),
+            (InputType::GenericVoice, "generic-voice"),
+            (InputType::Speechmatics, "speechmatics"),
+            (InputType::MihupAsr, "mihup-asr"),
+            (InputType::Mawdoo3, "mawdoo3"),
+            (InputType::OcrSdk, "ocr-sdk"),
+        ];
+        for (input_type, expected) in cases {
+            assert_eq!(input_type.to_string(), expected);
+            assert_eq!(expected.parse::<InputType>(), Ok(input_type));
+            assert_eq!(
+                serde_json::to_value(input_type).unwrap(),
+                input_type.as_str()
+            );
+            let map = Autosuggest::new("filled.count.soap")
+                .input_type(input_type)
+                .to_hash_map()
+                .unwrap();
+            assert_eq!(map.get("input-type"), Some(&expected.to_string()));
+            let map = Autosuggest::new("filled.count.soap")
+                .input_type_str(expected)
+                .unwrap()
+                .to_hash_map()
+                .unwrap();
+            assert_eq!(map.get("input-type"), Some(&expected.to_string()));
+        }
+        assert_eq!(
+            InputType::try_from("vocon_hybrid"),
+            Err(Error::InvalidParameter("Unknown autosuggest input type."))
+        );
+        assert!(Autosuggest::new("filled.count.soap")
+            .input_type_str("vocon_hybrid")
+            .is_err());
+    }
+
+    #[test]
+    fn test_autosuggest_n_results_validation() {
+        let autosuggest = Autosuggest::new("filled.count.so");
+        assert!(autosuggest.clone().n_results(1).validate().is_ok());
+        assert!(autosuggest.clone().n_results(100).validate().is_ok());
+        assert_eq!(
+            autosuggest.clone().n_results(0).validate(),
+            Err(Error::InvalidParameter(
+                "n-results must be between 1 and 100."
+            ))
+        );
+        assert!(autosuggest.clone().n_results(101).validate().is_err());
+
+        let focused = autosuggest.n_results(5);
+        assert!(focused.clone().n_focus_result(5).validate().is_ok());
+        assert_eq!(
+            focused.clone().n_focus_result(6).validate(),
+            Err(Error::InvalidParameter(
+                "n-focus-result must be a number no greater than n-results."
+            ))
+        );
+        assert_eq!(
+            Autosuggest::new("filled.count.so")
+                .n_focus_result(0)
+                .validate(),
+            Err(Error::InvalidParameter(
+                "n-focus-result must be a number between 1 and 100."
+            ))
+        );
+        assert!(Autosuggest::new("filled.count.so")
+            .n_focus_result(101)
+            .validate()
+            .is_err());
+
+        let params = focused.n_focus_result(3).to_hash_map().unwrap();
+        assert_eq!(params.get("n-focus-result"), Some(&"3".to_string()));
+    }
+
+    #[test]
+    fn test_autosuggest_clip_to_circle_units() {
+        let clip_to_circle = |circle: Circle| {
+            Autosuggest::new("filled.count.so")
+                .clip_to_circle(&circle)
+                .to_hash_map()
+                .unwrap()
+                .remove("clip-to-circle")
+                .unwrap()
+        };
+        assert_eq!(
+            clip_to_circle(Circle::new(51.521251, -0.203586, 10)),
+            "51.521251,-0.203586,10"
+        );
+        assert_eq!(
+            clip_to_circle(Circle::from_km(51.521251, -0.203586, 2.5)),
+            "51.521251,-0.203586,2.5"
+        );
+        assert_eq!(
+            clip_to_circle(Circle::from_meters(51.521251, -0.203586, 1500.0)),
+            "51.521251,-0.203586,1.5"
+        );
+    }
+
+    #[test]
+    fn test_autosuggest_result_serialize_round_trip() {
+        let payload = serde_json::json!({
+            "suggestions": [
+                {
+                    "country": "GB",
+                    "nearestPlace": "Bayswater, London",
+                    "words": "filled.count.soap",
+                    "rank": 1,
+                    "language": "en",
+                    "distanceToFocusKm": 0.5
+                },
+                {
+                    "country": "GB",
+                    "nearestPlace": "Bayswater, London",
+                    "words": "filled.count.soaps",
+                    "rank": 2,
+                    "language": "en",
+                    "square": {
+                        "southwest": { "lng": -0.203607, "lat": 51.521241 },
+                        "northeast": { "lng": -0.203575, "lat": 51.521261 }
+                    },
+                    "coordinates": { "lng": -0.203586, "lat": 51.521251 },
+                    "map": "https://w3w.co/filled.count.soaps"
+                }
+            ]
+        });
+        let result: AutosuggestResult = serde_json::from_value(payload.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&result).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_suggestion_serialize_round_trip() {
+        let payload = serde_json::json!({
+            "country": "GB",
+            "nearestPlace": "Bayswater, London",
+            "words": "filled.count.soap",
+            "rank": 1,
+            "language": "en",
+            "distanceToFocusKm": 10.5,
+            "coordinates": { "lng": -0.203586, "lat": 51.521251 }
+        });
+        let suggestion: Suggestion = serde_json::from_value(payload.clone()).unwrap();
+        let json = serde_json::to_string(&suggestion).unwrap();
+        let round_tripped: Suggestion = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_value(&round_tripped).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_suggestion_fractional_distance_to_focus() {
+        let suggestion = |distance: &str| {
+            serde_json::from_str::<Suggestion>(&format!(
+                r#"{{"country":"GB","nearestPlace":"Bayswater, London","words":"filled.count.soap","rank":1,"language":"en","distanceToFocusKm":{distance}}}"#
+            ))
+            .unwrap()
+        };
+        assert_eq!(suggestion("10.5").distance_to_focus_km, Some(10.5));
+        assert_eq!(suggestion("10").distance_to_focus_km, Some(10.0));
+    }
+
+    #[test]
+    fn test_suggestion_display() {
+        let suggestion = Suggestion {
+            country: "GB".to_string(),
+            nearest_place: "Bayswater, London".to_string(),
+            words: "filled.count.soap".to_string(),
+            rank: 1,
+            language: "en".to_string(),
+            distance_to_focus_km: None,
+            square: None,
+            coordinates: None,
+            map: None,
+        };
+        assert_eq!(
+            suggestion.to_string(),
+            "filled.count.soap [rank 1] — Bayswater, London"
+        );
+    }
+
+    #[test]
+    fn test_autosuggest_voice_input() {
+        let transcript = serde_json::json!({ "words": ["filled", "count", "soap"] });
+        let autosuggest = Autosuggest::builder()
+            .voice_input(transcript.clone())
+            .language("en");
+        let map = autosuggest.to_hash_map().unwrap();
+        assert_eq!(map.get("input-type"), Some(&"generic-voice".to_string()));
+        assert_eq!(map.get("input"), None);
+        assert_eq!(autosuggest.voice_input_body(), Some(&transcript));
+
+        let map = autosuggest
+            .clone()
+            .input_type(InputType::Speechmatics)
+            .to_hash_map()
+            .unwrap();
+        assert_eq!(map.get("input-type"), Some(&"speechmatics".to_string()));
+
+        assert_eq!(
+            autosuggest.input_type(InputType::Text).validate(),
+            Err(Error::InvalidParameter(
+                "A voice input needs a voice input-type, such as generic-voice."
+            ))
+        );
+    }
+
+    #[test]
+    fn test_autosuggest_result_iteration_and_selection() {
+        let suggestion = |words: &str, rank: u32, distance: Option<f64>| Suggestion {
+            country: "GB".to_string(),
+            nearest_place: "London".to_string(),
+            words: words.to_string(),
+            rank,
+            language: "en".to_string(),
+            distance_to_focus_km: distance,
+            square: None,
+            coordinates: None,
+            map: None,
+        };
+
+        let empty = AutosuggestResult {
+            suggestions: vec![],
+        };
+        assert!(empty.best_suggestion().is_none());
+        assert!(empty.focused_suggestion().is_none());
+        assert_eq!((&empty).into_iter().count(), 0);
+
+        let unfocused = AutosuggestResult {
+            suggestions: vec![suggestion("filled.count.soap", 1, None)],
+        };
+        assert!(unfocused.focused_suggestion().is_none());
+
+        let result = AutosuggestResult {
+            suggestions: vec![
+                suggestion("filled.count.soaps", 1, Some(12.5)),
+                suggestion("filled.count.soap", 2, None),
+                suggestion("filled.counts.soap", 3, Some(0.5)),
+                suggestion("filled.count.soapy", 4, Some(3.0)),
+            ],
+        };
+        assert_eq!(
+            result.best_suggestion().unwrap().words,
+            "filled.count.soaps"
+        );
+        assert_eq!(
+            result.focused_suggestion().unwrap().words,
+            "filled.counts.soap"
+        );
+
+        let mut ranks = Vec::new();
+        for suggestion in &result {
+            ranks.push(suggestion.rank);
+        }
+        assert_eq!(ranks, [1, 2, 3, 4]);
+        let words = result
+            .into_iter()
+            .map(|suggestion| suggestion.words)
+            .collect::<Vec<_>>();
+        assert_eq!(words[1], "filled.count.soap");
+    }
+
+    #[test]
+    fn test_suggestion_ordering() {
+        let suggestion = |words: &str, rank: u32, distance: Option<f64>| Suggestion {
+            country: "GB".to_string(),
+            nearest_place: "London".to_string(),
+            words: words.to_string(),
+            rank,
+            language: "en".to_string(),
+            distance_to_focus_km: distance,
+            square: None,
+            coordinates: None,
+            map: None,
+        };
+
+        assert_eq!(
+            suggestion("filled.count.soap", 1, None),
+            suggestion("filled.count.soap", 3, Some(2.0))
+        );
+        assert_ne!(
+            suggestion("filled.count.soap", 1, None),
+            suggestion("filled.count.soaps", 1, None)
+        );
+
+        let mut suggestions = [
+            suggestion("filled.counts.soap", 3, Some(0.5)),
+            suggestion("filled.count.soaps", 1, None),
+            suggestion("filled.count.soapy", 2, Some(4.0)),
+        ];
+        suggestions.sort_by(Suggestion::by_rank);
+        let ranks = suggestions.iter().map(|s| s.rank).collect::<Vec<_>>();
+        assert_eq!(ranks, [1, 2, 3]);
+
+        // Different words: ordered by words and both kept in a set
+        let a = suggestion("filled.count.soap", 1, None);
+        let b = suggestion("filled.count.soaps", 1, None);
+        assert_eq!(a.cmp(&b), Ordering::Less);
+        assert_eq!(a.cmp(&a.clone()), Ordering::Equal);
+        let set = std::collections::BTreeSet::from([a, b]);
+        assert_eq!(set.len(), 2);
+
+        suggestions.sort_by(Suggestion::by_distance);
+        let words = suggestions
+            .iter()
+            .map(|s| s.words.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            words,
+            [
+                "filled.counts.soap",
+                "filled.count.soapy",
+                "filled.count.soaps"
+            ]
+        );
+
+        // Merged results may repeat words at different ranks: `Ord` agrees with `==`
+        let mut merged = vec![
+            suggestion("filled.count.soap", 3, None),
+            suggestion("filled.count.soaps", 1, None),
+            suggestion("filled.count.soap", 1, None),
+            suggestion("filled.count.soapy", 2, None),
+            suggestion("filled.count.soaps", 2, None),
+        ];
+        merged.sort_by(Suggestion::by_rank);
+        let ranked = merged
+            .iter()
+            .map(|s| (s.words.as_str(), s.rank))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ranked,
+            [
+                ("filled.count.soap", 1),
+                ("filled.count.soaps", 1),
+                ("filled.count.soaps", 2),
+                ("filled.count.soapy", 2),
+                ("filled.count.soap", 3)
+            ]
+        );
+        merged.sort();
+        merged.dedup();
+        let words = merged.iter().map(|s| s.words.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            words,
+            [
+                "filled.count.soap",
+                "filled.count.soaps",
+                "filled.count.soapy"
+            ]
+        );
+        let set = merged
+            .into_iter()
+            .collect::<std::collections::BTreeSet<_>>();
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn test_autosuggest_to_query_string() {
+        let query = Autosuggest::new("filled.count.so")
+            .focus(&Coordinates::new(51.521251, -0.203586))
+            .n_results(5)
+            .language("en")
+            .to_query_string()
+            .unwrap();
+        assert_eq!(
+            query,
+            "focus=51.521251%2C-0.203586&input=filled.count.so&language=en&n-results=5"
+        );
+        assert_eq!(
+            Autosuggest::new("index home raft").to_query_string(),
+            Ok("input=index+home+raft".to_string())
+        );
+        assert!(Autosuggest::builder().to_query_string().is_err());
+    }
+
+    #[test]
+    fn test_autosuggest_validate_all() {
+        assert!(Autosuggest::new("filled.count.so")
+            .validate_all()
+            .is_empty());
+
+        let autosuggest = Autosuggest::builder()
+            .n_results(0)
+            .n_focus_result(0)
+            .clip_to_polygon(&Polygon::new(&[Coordinates::new(51.521251, -0.203586)]));
+        let errors = autosuggest.validate_all();
+        assert_eq!(
+            errors,
+            vec![
+                Error::InvalidParameter("An autosuggest input is required."),
+                Error::InvalidParameter("n-results must be between 1 and 100."),
+                Error::InvalidParameter("n-focus-result must be a number between 1 and 100."),
+                Error::InvalidParameter("A polygon must have at least 4 coordinates."),
+            ]
+        );
+        assert_eq!(
+            autosuggest.validate(),
+            Err(Error::InvalidParameter("An autosuggest input is required."))
+        );
+    }
 }
@@ -0,0 +1,81 @@
+use std::{fmt, ops::Deref, str::FromStr};
+
+use serde::Serialize;
+
+use crate::service::Error;
+
+/// An ISO 3166-1 alpha-2 country code, such as `GB`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct CountryCode(String);
+
+impl CountryCode {
+    pub fn new(code: &str) -> Result<CountryCode, Error> {
+        if code.len() != 2 || !code.bytes().all(|byte| byte.is_ascii_uppercase()) {
+            return Err(Error::InvalidParameter(
+                "A country code must be two upper-case letters, such as GB.",
+            ));
+        }
+        Ok(CountryCode(code.to_string()))
+    }
+}
+
+impl fmt::Display for CountryCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for CountryCode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CountryCode::new(s)
+    }
+}
+
+impl TryFrom<&str> for CountryCode {
+    type Error = Error;
+
+    fn try_from(code: &str) -> Result<Self, Self::Error> {
+        CountryCode::new(code)
+    }
+}
+
+impl Deref for CountryCode {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod country_tests {
+    use super::*;
+
+    #[test]
+    fn test_country_code_valid() {
+        let code = CountryCode::new("GB").unwrap();
+        assert_eq!(code.to_string(), "GB");
+        assert_eq!(&*code, "GB");
+        assert_eq!(
+            "US".parse::<CountryCode>().unwrap(),
+            CountryCode::new("US").unwrap()
+        );
+        assert_eq!(CountryCode::try_from("FR").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_country_code_rejects_lower_case() {
+        assert!(CountryCode::new("gb").is_err());
+        assert!(CountryCode::new("Gb").is_err());
+    }
+
+    #[test]
+    fn test_country_code_rejects_wrong_length() {
+        assert!(CountryCode::new("GBR").is_err());
+        assert!(CountryCode::new("G").is_err());
+        assert!(CountryCode::new("").is_err());
+        assert!(CountryCode::new("12").is_err());
+    }
+}
@@ -0,0 +1,30 @@
+use serde::{
+    de::{Deserializer, IgnoredAny},
+    Deserialize, Serialize,
+};
+
+/// The result of a call whose successful response carries no content, such as
+/// `autosuggest_selection`. Any body the API does send is ignored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Empty;
+
+impl<'de> Deserialize<'de> for Empty {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        IgnoredAny::deserialize(deserializer).map(|_| Empty)
+    }
+}
+
+#[cfg(test)]
+mod empty_tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_ignores_any_body() {
+        assert_eq!(serde_json::from_str::<Empty>("null").unwrap(), Empty);
+        assert_eq!(serde_json::from_str::<Empty>("{}").unwrap(), Empty);
+        assert_eq!(
+            serde_json::from_str::<Empty>(r#"{"status":"ok"}"#).unwrap(),
+            Empty
+        );
+    }
+}
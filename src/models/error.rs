@@ -1,12 +1,13 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize)]
-pub(crate) struct Error {
+/// The `error` object of an API error response; surfaced to callers as [`crate::Error::Api`].
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct ErrorDetail {
     pub code: String,
     pub message: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub(crate) struct ErrorResult {
-    pub error: Error,
+    pub error: ErrorDetail,
 }
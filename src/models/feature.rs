@@ -1,10 +1,48 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct Feature<T> {
+/// A GeoJSON feature with geometry `T`. Properties stay as raw JSON unless a typed `P` is given.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Feature<T, P = serde_json::Value> {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub bbox: Option<Vec<f64>>,
     pub geometry: T,
     #[serde(rename = "type")]
     pub kind: String,
-    pub properties: serde_json::Value,
+    pub properties: P,
+}
+
+impl<T> Feature<T> {
+    /// Returns the value stored under `key` in the feature's `properties`, if any.
+    pub fn property(&self, key: &str) -> Option<&serde_json::Value> {
+        self.properties.get(key)
+    }
+}
+
+#[cfg(test)]
+mod feature_tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_feature_property() {
+        let feature: Feature<serde_json::Value> = serde_json::from_value(json!({
+            "type": "Feature",
+            "geometry": { "type": "Point", "coordinates": [-0.203586, 51.521251] },
+            "properties": {
+                "words": "filled.count.soap",
+                "country": "GB"
+            }
+        }))
+        .unwrap();
+        assert_eq!(feature.property("words"), Some(&json!("filled.count.soap")));
+        assert_eq!(feature.property("missing"), None);
+
+        let feature: Feature<serde_json::Value> = serde_json::from_value(json!({
+            "type": "Feature",
+            "geometry": null,
+            "properties": {}
+        }))
+        .unwrap();
+        assert_eq!(feature.property("words"), None);
+    }
 }
@@ -1,33 +1,81 @@
-use std::fmt;
+use std::{collections::HashSet, fmt, str::FromStr};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::Coordinates;
+use crate::{
+    service::{format_with_precision, Error, Validator},
+    Coordinates,
+};
 
 use super::feature::Feature;
 
+/// The most tiles `BoundingBox::tiles` will split a box into.
+const MAX_TILES: usize = 10_000;
+
 pub trait FormattedGridSection {
     fn format() -> &'static str;
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Line {
     pub start: Coordinates,
     pub end: Coordinates,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct GridSection {
     pub lines: Vec<Line>,
 }
 
+impl GridSection {
+    /// Returns each distinct line endpoint once, in the order first seen.
+    pub fn points(&self) -> Vec<Coordinates> {
+        // Keyed on bit patterns, with `+ 0.0` folding -0.0 into 0.0 as `==` does.
+        let key = |point: &Coordinates| ((point.lat + 0.0).to_bits(), (point.lng + 0.0).to_bits());
+        let mut seen = HashSet::new();
+        self.lines
+            .iter()
+            .flat_map(|line| [&line.start, &line.end])
+            .filter(|point| seen.insert(key(point)))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the line closest to `point`, measured as the perpendicular distance to each
+    /// segment, or `None` if the grid has no lines.
+    pub fn nearest_line(&self, point: &Coordinates) -> Option<&Line> {
+        self.lines
+            .iter()
+            .min_by(|a, b| a.distance_to(point).total_cmp(&b.distance_to(point)))
+    }
+}
+
+impl Line {
+    /// Approximate distance from `point` to this segment, in degrees of latitude. Longitudes are
+    /// scaled by the cosine of the point's latitude, which is accurate at grid section scale.
+    fn distance_to(&self, point: &Coordinates) -> f64 {
+        let scale = point.lat.to_radians().cos();
+        let project = |c: &Coordinates| ((c.lng - point.lng) * scale, c.lat - point.lat);
+        let (x1, y1) = project(&self.start);
+        let (x2, y2) = project(&self.end);
+        let (dx, dy) = (x2 - x1, y2 - y1);
+        let length_squared = dx * dx + dy * dy;
+        let t = if length_squared == 0.0 {
+            0.0
+        } else {
+            (-(x1 * dx + y1 * dy) / length_squared).clamp(0.0, 1.0)
+        };
+        (x1 + t * dx).hypot(y1 + t * dy)
+    }
+}
+
 impl FormattedGridSection for GridSection {
     fn format() -> &'static str {
         "json"
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct GridSectionGeoJson {
     pub features: Vec<Feature<Geometry>>,
     #[serde(rename = "type")]
@@ -40,14 +88,14 @@ impl FormattedGridSection for GridSectionGeoJson {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Geometry {
     pub coordinates: Vec<Vec<Vec<f32>>>,
     #[serde(rename = "type")]
     pub kind: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct BoundingBox {
     southwest: Coordinates,
     northeast: Coordinates,
@@ -57,15 +105,27 @@ impl fmt::Display for BoundingBox {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{},{},{},{}",
-            self.southwest.lat, self.southwest.lng, self.northeast.lat, self.northeast.lng
+            "{},{}",
+            format_with_precision(&self.southwest, f.precision()),
+            format_with_precision(&self.northeast, f.precision())
         )
     }
 }
 
+/// Parses the `"sw_lat,sw_lng,ne_lat,ne_lng"` form produced by `Display`, rejecting
+/// out-of-range or misordered corners.
+impl FromStr for BoundingBox {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BoundingBox::parse_validated(s, false)
+    }
+}
+
 impl BoundingBox {
+    /// Creates a box without checking its corners are ordered; debug builds assert they are.
     pub fn new(sw_lat: f64, sw_lng: f64, ne_lat: f64, ne_lng: f64) -> Self {
-        Self {
+        let bounding_box = Self {
             southwest: Coordinates {
                 lat: sw_lat,
                 lng: sw_lng,
@@ -74,6 +134,426 @@ impl BoundingBox {
                 lat: ne_lat,
                 lng: ne_lng,
             },
+        };
+        debug_assert!(
+            bounding_box.is_valid(),
+            "the southwest corner of {} is not below and left of its northeast corner",
+            bounding_box
+        );
+        bounding_box
+    }
+
+    /// Like `new`, but rejects non-finite values and a southwest corner that lies above or right
+    /// of the northeast corner. Equal corners, a degenerate box, are allowed.
+    pub fn new_checked(
+        sw_lat: f64,
+        sw_lng: f64,
+        ne_lat: f64,
+        ne_lng: f64,
+    ) -> Result<BoundingBox, Error> {
+        if ![sw_lat, sw_lng, ne_lat, ne_lng]
+            .iter()
+            .all(|value| value.is_finite())
+        {
+            return Err(Error::InvalidParameter(
+                "Bounding box values must be finite numbers.",
+            ));
+        }
+        if sw_lat > ne_lat {
+            return Err(Error::InvalidParameter(
+                "The southwest latitude must not be greater than the northeast latitude.",
+            ));
+        }
+        if sw_lng > ne_lng {
+            return Err(Error::InvalidParameter(
+                "The southwest longitude must not be greater than the northeast longitude.",
+            ));
+        }
+        Ok(BoundingBox::new(sw_lat, sw_lng, ne_lat, ne_lng))
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.southwest.lat <= self.northeast.lat && self.southwest.lng <= self.northeast.lng
+    }
+
+    pub fn contains(&self, coordinates: &Coordinates) -> bool {
+        (self.southwest.lat..=self.northeast.lat).contains(&coordinates.lat)
+            && (self.southwest.lng..=self.northeast.lng).contains(&coordinates.lng)
+    }
+
+    pub fn southwest(&self) -> &Coordinates {
+        &self.southwest
+    }
+
+    pub fn northeast(&self) -> &Coordinates {
+        &self.northeast
+    }
+
+    pub fn center(&self) -> Coordinates {
+        Coordinates::new(
+            (self.southwest.lat + self.northeast.lat) / 2.0,
+            (self.southwest.lng + self.northeast.lng) / 2.0,
+        )
+    }
+
+    /// Returns the smallest box containing both `self` and `other`. Boxes never wrap across the
+    /// antimeridian, so merging boxes on either side of it spans the whole longitude range.
+    pub fn merge(&self, other: &BoundingBox) -> BoundingBox {
+        BoundingBox::new(
+            self.southwest.lat.min(other.southwest.lat),
+            self.southwest.lng.min(other.southwest.lng),
+            self.northeast.lat.max(other.northeast.lat),
+            self.northeast.lng.max(other.northeast.lng),
+        )
+    }
+
+    /// Splits the box into row-major tiles of at most `tile_size` degrees on each side, e.g. to
+    /// stay under the API's grid section size limit. A non-positive `tile_size` returns the box
+    /// itself, and one that would need more than 10,000 tiles is rejected.
+    pub fn tiles(&self, tile_size: f64) -> Result<Vec<BoundingBox>, Error> {
+        if tile_size.is_nan() || tile_size <= 0.0 {
+            return Ok(vec![self.clone()]);
+        }
+        // The float-to-int cast saturates, so a tiny tile size can't wrap around
+        let steps = |from: f64, to: f64| (((to - from) / tile_size).ceil() as usize).max(1);
+        let rows = steps(self.southwest.lat, self.northeast.lat);
+        let columns = steps(self.southwest.lng, self.northeast.lng);
+        let count = rows
+            .checked_mul(columns)
+            .filter(|count| *count <= MAX_TILES)
+            .ok_or(Error::InvalidParameter(
+                "The tile size is too small; it would need more than 10,000 tiles.",
+            ))?;
+        let mut tiles = Vec::with_capacity(count);
+        for row in 0..rows {
+            let sw_lat = self.southwest.lat + row as f64 * tile_size;
+            let ne_lat = (sw_lat + tile_size).min(self.northeast.lat);
+            for column in 0..columns {
+                let sw_lng = self.southwest.lng + column as f64 * tile_size;
+                let ne_lng = (sw_lng + tile_size).min(self.northeast.lng);
+                tiles.push(BoundingBox::new(sw_lat, sw_lng, ne_lat, ne_lng));
+            }
+        }
+        Ok(tiles)
+    }
+
+    /// Roughly how many lines a grid section request for this box returns, treating the grid as
+    /// 3m squares: one line per 3m of height plus one per 3m of width. Useful for progress
+    /// reporting, not as an exact count.
+    pub fn estimated_grid_lines(&self) -> usize {
+        const METERS_PER_DEGREE: f64 = 111_320.0;
+        const SQUARE_SIZE_METERS: f64 = 3.0;
+        // One line per square boundary crossed, plus the two lines on the box's own edges.
+        const EDGE_LINES: usize = 2;
+        let mid_lat = ((self.southwest.lat + self.northeast.lat) / 2.0).to_radians();
+        let height = (self.northeast.lat - self.southwest.lat).abs() * METERS_PER_DEGREE;
+        let width =
+            (self.northeast.lng - self.southwest.lng).abs() * METERS_PER_DEGREE * mid_lat.cos();
+        let lines = (height / SQUARE_SIZE_METERS).ceil() + (width / SQUARE_SIZE_METERS).ceil();
+        lines as usize + EDGE_LINES
+    }
+
+    /// Parses `"sw_lat,sw_lng,ne_lat,ne_lng"` and checks both corners are in range. Corners given
+    /// the wrong way round are swapped when `reorder` is set, and rejected otherwise.
+    pub fn parse_validated(s: &str, reorder: bool) -> Result<BoundingBox, Error> {
+        let values = s
+            .split(',')
+            .map(|value| value.trim().parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| Error::InvalidParameter("Bounding box values must be numbers."))?;
+        let [sw_lat, sw_lng, ne_lat, ne_lng] = values[..] else {
+            return Err(Error::InvalidParameter(
+                "A bounding box must contain exactly four values.",
+            ));
+        };
+        Coordinates::new(sw_lat, sw_lng).validate()?;
+        Coordinates::new(ne_lat, ne_lng).validate()?;
+        if reorder {
+            Ok(BoundingBox::new(
+                sw_lat.min(ne_lat),
+                sw_lng.min(ne_lng),
+                sw_lat.max(ne_lat),
+                sw_lng.max(ne_lng),
+            ))
+        } else {
+            BoundingBox::new_checked(sw_lat, sw_lng, ne_lat, ne_lng)
+        }
+    }
+}
+
+#[cfg(test)]
+mod gridsection_tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_grid_section_points() {
+        let grid_section: GridSection = serde_json::from_value(json!({
+            "lines": [
+                {
+                    "start": {
+                        "lng": 0.116126,
+                        "lat": 52.207988
+                    },
+                    "end": {
+                        "lng": 0.11754,
+                        "lat": 52.208867
+                    }
+                },
+                {
+                    "start": {
+                        "lng": 0.11754,
+                        "lat": 52.208867
+                    },
+                    "end": {
+                        "lng": 0.116126,
+                        "lat": 52.208867
+                    }
+                }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            grid_section.points(),
+            vec![
+                Coordinates::new(52.207988, 0.116126),
+                Coordinates::new(52.208867, 0.11754),
+                Coordinates::new(52.208867, 0.116126),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bounding_box_display_precision() {
+        let bounding_box = BoundingBox::new(52.207988, 0.116126, 52.208867, 0.11754);
+        assert_eq!(
+            bounding_box.to_string(),
+            "52.207988,0.116126,52.208867,0.11754"
+        );
+        assert_eq!(format!("{:.3}", bounding_box), "52.208,0.116,52.209,0.118");
+    }
+
+    #[test]
+    fn test_bounding_box_center() {
+        let bounding_box = BoundingBox::new(51.0, -1.0, 52.0, 1.0);
+        assert_eq!(bounding_box.center(), Coordinates::new(51.5, 0.0));
+        let degenerate = BoundingBox::new(51.5, -0.2, 51.5, -0.2);
+        assert_eq!(degenerate.center(), Coordinates::new(51.5, -0.2));
+        let antimeridian = BoundingBox::new(-10.0, 170.0, 10.0, 180.0);
+        assert_eq!(antimeridian.center(), Coordinates::new(0.0, 175.0));
+    }
+
+    #[test]
+    fn test_bounding_box_contains() {
+        let bounding_box = BoundingBox::new(51.0, -1.0, 52.0, 1.0);
+        assert!(bounding_box.contains(&Coordinates::new(51.5, 0.0)));
+        assert!(bounding_box.contains(&Coordinates::new(51.0, -1.0)));
+        assert!(bounding_box.contains(&Coordinates::new(52.0, 0.5)));
+        assert!(!bounding_box.contains(&Coordinates::new(52.000001, 0.0)));
+        assert!(!bounding_box.contains(&Coordinates::new(51.5, 1.000001)));
+
+        let antimeridian = BoundingBox::new(-10.0, 170.0, 10.0, 180.0);
+        assert!(antimeridian.contains(&Coordinates::new(0.0, 180.0)));
+        assert!(!antimeridian.contains(&Coordinates::new(0.0, -180.0)));
+    }
+
+    #[test]
+    fn test_bounding_box_merge() {
+        let london = BoundingBox::new(51.0, -1.0, 52.0, 1.0);
+        let inner = BoundingBox::new(51.2, -0.5, 51.8, 0.5);
+        assert_eq!(london.merge(&inner).to_string(), london.to_string());
+
+        let paris = BoundingBox::new(48.5, 2.0, 49.0, 2.5);
+        assert_eq!(london.merge(&paris).to_string(), "48.5,-1,52,2.5");
+        assert_eq!(paris.merge(&london).to_string(), "48.5,-1,52,2.5");
+
+        let east = BoundingBox::new(-10.0, 170.0, 10.0, 180.0);
+        let west = BoundingBox::new(-5.0, -180.0, 5.0, -170.0);
+        assert_eq!(east.merge(&west).to_string(), "-10,-180,10,180");
+    }
+
+    #[test]
+    fn test_bounding_box_parse_validated() {
+        let bounding_box = BoundingBox::parse_validated("51.0, -1.0, 52.0, 1.0", false).unwrap();
+        assert_eq!(bounding_box.to_string(), "51,-1,52,1");
+
+        let swapped = BoundingBox::parse_validated("52.0,1.0,51.0,-1.0", true).unwrap();
+        assert_eq!(swapped.to_string(), "51,-1,52,1");
+        assert!(matches!(
+            BoundingBox::parse_validated("52.0,1.0,51.0,-1.0", false),
+            Err(Error::InvalidParameter(_))
+        ));
+
+        for malformed in [
+            "51.0,-1.0,52.0",
+            "51.0,-1.0,52.0,east",
+            "",
+            "91.0,-1.0,92.0,1.0",
+        ] {
+            assert!(
+                matches!(
+                    BoundingBox::parse_validated(malformed, true),
+                    Err(Error::InvalidParameter(_))
+                ),
+                "{malformed}"
+            );
         }
     }
+
+    #[test]
+    fn test_bounding_box_estimated_grid_lines() {
+        // Roughly 98m tall by 98m wide at this latitude
+        let bounding_box = BoundingBox::new(52.207988, 0.116126, 52.208867, 0.11754);
+        let lines = bounding_box.estimated_grid_lines();
+        assert!((60..=72).contains(&lines), "{lines}");
+    }
+
+    #[test]
+    fn test_bounding_box_tiles() {
+        let bounding_box = BoundingBox::new(51.0, -1.0, 52.0, 0.5);
+        let tiles = bounding_box
+            .tiles(1.0)
+            .unwrap()
+            .iter()
+            .map(|tile| tile.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(tiles, vec!["51,-1,52,0", "51,0,52,0.5"]);
+        assert_eq!(bounding_box.tiles(0.0).unwrap().len(), 1);
+        assert_eq!(bounding_box.tiles(f64::NAN).unwrap().len(), 1);
+        assert_eq!(bounding_box.tiles(0.015).unwrap().len(), 67 * 100);
+        assert!(bounding_box.tiles(0.001).is_err());
+        assert!(bounding_box.tiles(f64::MIN_POSITIVE).is_err());
+        assert!(bounding_box.tiles(5e-324).is_err());
+    }
+
+    #[test]
+    fn test_bounding_box_new_checked() {
+        let degenerate = BoundingBox::new_checked(51.5, -0.2, 51.5, -0.2).unwrap();
+        assert!(degenerate.is_valid());
+        assert_eq!(
+            BoundingBox::new_checked(52.0, -1.0, 51.0, 1.0).unwrap_err(),
+            Error::InvalidParameter(
+                "The southwest latitude must not be greater than the northeast latitude."
+            )
+        );
+        assert_eq!(
+            BoundingBox::new_checked(51.0, 1.0, 52.0, -1.0).unwrap_err(),
+            Error::InvalidParameter(
+                "The southwest longitude must not be greater than the northeast longitude."
+            )
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "is not below and left of its northeast corner")]
+    fn test_bounding_box_new_asserts_order() {
+        BoundingBox::new(52.0, -1.0, 51.0, 1.0);
+    }
+
+    #[test]
+    fn test_grid_section_serialize_round_trip() {
+        let payload = json!({
+            "lines": [
+                {
+                    "start": { "lng": 0.116126, "lat": 52.207988 },
+                    "end": { "lng": 0.11754, "lat": 52.208867 }
+                }
+            ]
+        });
+        let grid_section: GridSection = serde_json::from_value(payload.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&grid_section).unwrap(), payload);
+
+        let payload = json!({
+            "type": "FeatureCollection",
+            "features": [{
+                "type": "Feature",
+                "geometry": {
+                    "type": "MultiLineString",
+                    "coordinates": [[[0.125, 52.25], [0.5, 52.5]]]
+                },
+                "properties": {}
+            }]
+        });
+        let geojson: GridSectionGeoJson = serde_json::from_value(payload.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&geojson).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_bounding_box_from_str() {
+        let bounding_box = BoundingBox::new(51.521, -0.343, 52.6, 2.3324);
+        let parsed = bounding_box.to_string().parse::<BoundingBox>().unwrap();
+        assert_eq!(parsed.to_string(), bounding_box.to_string());
+        assert_eq!(
+            " 51.521, -0.343, 52.6, 2.3324 "
+                .parse::<BoundingBox>()
+                .unwrap()
+                .to_string(),
+            "51.521,-0.343,52.6,2.3324"
+        );
+
+        assert!("51.521,-0.343,52.6".parse::<BoundingBox>().is_err());
+        assert!("51.521,-0.343,52.6,east".parse::<BoundingBox>().is_err());
+        assert!("".parse::<BoundingBox>().is_err());
+        assert!("51.521,-0.343,91,2.3324".parse::<BoundingBox>().is_err());
+        assert!("51.521,-181,52.6,2.3324".parse::<BoundingBox>().is_err());
+        assert!("52.6,-0.343,51.521,2.3324".parse::<BoundingBox>().is_err());
+    }
+
+    #[test]
+    fn test_bounding_box_corners() {
+        let bounding_box = BoundingBox::new(51.0, -1.0, 52.0, 1.0);
+        assert_eq!(bounding_box.southwest(), &Coordinates::new(51.0, -1.0));
+        assert_eq!(bounding_box.northeast(), &Coordinates::new(52.0, 1.0));
+    }
+
+    #[test]
+    fn test_grid_section_nearest_line() {
+        let grid_section: GridSection = serde_json::from_value(json!({
+            "lines": [
+                {
+                    "start": { "lng": 0.116126, "lat": 52.207988 },
+                    "end": { "lng": 0.11754, "lat": 52.207988 }
+                },
+                {
+                    "start": { "lng": 0.116126, "lat": 52.208867 },
+                    "end": { "lng": 0.11754, "lat": 52.208867 }
+                },
+                {
+                    "start": { "lng": 0.116126, "lat": 52.207988 },
+                    "end": { "lng": 0.116126, "lat": 52.208867 }
+                }
+            ]
+        }))
+        .unwrap();
+
+        // Just below the top line, well away from its midpoint.
+        let line = grid_section
+            .nearest_line(&Coordinates::new(52.2088, 0.1174))
+            .unwrap();
+        assert_eq!(line.start, Coordinates::new(52.208867, 0.116126));
+        assert_eq!(line.end, Coordinates::new(52.208867, 0.11754));
+
+        // Beside the vertical line, halfway up.
+        let line = grid_section
+            .nearest_line(&Coordinates::new(52.2084, 0.11615))
+            .unwrap();
+        assert_eq!(line.end, Coordinates::new(52.208867, 0.116126));
+
+        let empty = GridSection { lines: vec![] };
+        assert!(empty
+            .nearest_line(&Coordinates::new(52.2084, 0.1165))
+            .is_none());
+    }
+
+    #[test]
+    fn test_bounding_box_rejects_nan() {
+        assert!(BoundingBox::new_checked(f64::NAN, 0.0, 1.0, 1.0).is_err());
+        assert!(BoundingBox::new_checked(0.0, 0.0, 1.0, f64::INFINITY).is_err());
+        assert!(BoundingBox::parse_validated("NaN,0,1,1", false).is_err());
+        assert!(BoundingBox::parse_validated("0,0,1,NaN", true).is_err());
+        assert!("NaN,0,1,1".parse::<BoundingBox>().is_err());
+        assert!("0,inf,1,1".parse::<BoundingBox>().is_err());
+    }
 }
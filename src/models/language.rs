@@ -1,6 +1,6 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Language {
     #[serde(rename = "nativeName")]
     pub native_name: String,
@@ -8,7 +8,7 @@ pub struct Language {
     pub name: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct AvailableLanguages {
     pub languages: Vec<Language>,
 }
@@ -1,15 +1,18 @@
-use serde::Deserialize;
-use std::{collections::HashMap, fmt};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt, str::FromStr};
 
-use crate::service::{Error, ToHashMap, Validator};
+use crate::service::{format_degrees, format_with_precision, Error, ToHashMap, Validator};
 
-use super::feature::Feature;
+use super::{autosuggest::Suggestion, feature::Feature, gridsection::BoundingBox};
+
+/// Mean radius of the Earth in metres, as used for haversine distances.
+const EARTH_RADIUS_METRES: f64 = 6_371_008.8;
 
 pub trait FormattedAddress {
     fn format() -> &'static str;
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ConvertTo3wa {
     coordinates: Option<Coordinates>,
     locale: Option<String>,
@@ -18,12 +21,16 @@ pub struct ConvertTo3wa {
 
 impl ToHashMap for ConvertTo3wa {
     fn to_hash_map<'a>(&self) -> Result<HashMap<&'a str, String>, Error> {
+        self.to_hash_map_with_precision(None)
+    }
+
+    fn to_hash_map_with_precision<'a>(
+        &self,
+        precision: Option<usize>,
+    ) -> Result<HashMap<&'a str, String>, Error> {
         let mut map = HashMap::new();
         if let Some(coordinates) = &self.coordinates {
-            map.insert(
-                "coordinates",
-                format!("{},{}", coordinates.lat, coordinates.lng),
-            );
+            map.insert("coordinates", format_with_precision(coordinates, precision));
         }
         if let Some(ref locale) = &self.locale {
             map.insert("locale", locale.into());
@@ -55,14 +62,29 @@ impl ConvertTo3wa {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ConvertToCoordinates {
     locale: Option<String>,
     words: Option<String>,
 }
 
+impl Validator for ConvertToCoordinates {
+    fn validate(&self) -> Result<(), Error> {
+        // A common mix-up is passing coordinates to the words-to-coordinates conversion
+        if let Some(ref words) = &self.words {
+            if Coordinates::parse_flexible(words).is_ok() {
+                return Err(Error::InvalidParameter(
+                    "The words look like coordinates; use convert_to_3wa to convert coordinates.",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
 impl ToHashMap for ConvertToCoordinates {
     fn to_hash_map<'a>(&self) -> Result<HashMap<&'a str, String>, Error> {
+        self.validate()?;
         let mut map = HashMap::new();
         if let Some(ref locale) = &self.locale {
             map.insert("locale", locale.into());
@@ -85,9 +107,16 @@ impl ConvertToCoordinates {
         self.locale = Some(locale.into());
         self
     }
+    /// Removes the `///` prefix commonly copied along with an address from maps and links.
+    pub fn strip_slashes(mut self) -> Self {
+        self.words = self
+            .words
+            .map(|words| words.trim_start_matches('/').to_string());
+        self
+    }
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct Coordinates {
     pub lat: f64,
     pub lng: f64,
@@ -95,36 +124,306 @@ pub struct Coordinates {
 
 impl fmt::Display for Coordinates {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{},{}", self.lat, self.lng)
+        write!(
+            f,
+            "{},{}",
+            format_degrees(self.lat, f.precision()),
+            format_degrees(self.lng, f.precision())
+        )
     }
 }
 
 impl Coordinates {
+    /// Creates coordinates without range checks, for values that are already known to be valid.
     pub fn new(lat: f64, lng: f64) -> Self {
         Self { lat, lng }
     }
+
+    /// Like `new`, but rejects a latitude outside -90..=90 or a longitude outside -180..=180.
+    pub fn try_new(lat: f64, lng: f64) -> Result<Coordinates, Error> {
+        let coordinates = Self::new(lat, lng);
+        coordinates.validate()?;
+        Ok(coordinates)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// Parses strict `"lat,lng"` as well as looser forms such as `"51.521251, -0.203586"`,
+    /// `"51.521251 -0.203586"`, `"lat: 51.521251, lng: -0.203586"` or `"N51.521251 W0.203586"`.
+    /// A hemisphere letter before or after a value sets its sign; it must be `N`/`S` for the
+    /// latitude and `E`/`W` for the longitude, and the value itself must not be negative.
+    pub fn parse_flexible(s: &str) -> Result<Coordinates, Error> {
+        let normalized = s.to_ascii_lowercase();
+        let mut values: Vec<(&str, Option<char>)> = Vec::new();
+        // A hemisphere written apart from its value, e.g. the "N" in "N 51.521251"
+        let mut pending = None;
+        for part in normalized.split(|c: char| c == ',' || c.is_whitespace()) {
+            let part = strip_coordinate_label(part);
+            if part.is_empty() {
+                continue;
+            }
+            if let [hemisphere @ (b'n' | b's' | b'e' | b'w')] = part.as_bytes() {
+                match values.last_mut() {
+                    Some((_, previous @ None)) if pending.is_none() => {
+                        *previous = Some(*hemisphere as char)
+                    }
+                    _ if pending.is_none() => pending = Some(*hemisphere as char),
+                    _ => return Err(Error::InvalidParameter(MISPLACED_HEMISPHERE)),
+                }
+                continue;
+            }
+            let (value, hemisphere) = split_hemisphere(part);
+            let hemisphere = match (pending.take(), hemisphere) {
+                (Some(_), Some(_)) => return Err(Error::InvalidParameter(MISPLACED_HEMISPHERE)),
+                (pending, hemisphere) => pending.or(hemisphere),
+            };
+            values.push((value, hemisphere));
+        }
+        if pending.is_some() {
+            return Err(Error::InvalidParameter(MISPLACED_HEMISPHERE));
+        }
+        let [(lat, lat_hemisphere), (lng, lng_hemisphere)] = values[..] else {
+            return Err(Error::InvalidParameter(
+                "Coordinates must contain exactly a latitude and a longitude.",
+            ));
+        };
+        let coordinates = Coordinates::new(
+            signed_degrees(lat, lat_hemisphere, ('n', 's'))?,
+            signed_degrees(lng, lng_hemisphere, ('e', 'w'))?,
+        );
+        coordinates.validate()?;
+        Ok(coordinates)
+    }
+
+    /// Great-circle distance to `other` in metres, using the haversine formula on a spherical
+    /// Earth of mean radius.
+    pub fn distance_to(&self, other: &Coordinates) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let d_lat = lat2 - lat1;
+        let d_lng = (other.lng - self.lng).to_radians();
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lng / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_METRES * a.sqrt().min(1.0).asin()
+    }
+
+    /// Initial bearing towards `other` in degrees clockwise from north, in `0.0..360.0`.
+    pub fn bearing_to(&self, other: &Coordinates) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let d_lng = (other.lng - self.lng).to_radians();
+        let y = d_lng.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * d_lng.cos();
+        y.atan2(x).to_degrees().rem_euclid(360.0)
+    }
+
+    /// Returns a box reaching `radius_m` metres from this point in each cardinal direction,
+    /// e.g. for `clip-to-bounding-box`. Latitudes are clamped at the poles and longitudes at
+    /// ±180; a box that reaches a pole spans every longitude. The sign of `radius_m` is ignored,
+    /// and a non-finite radius or out of range point is an error.
+    pub fn bounding_box(&self, radius_m: f64) -> Result<BoundingBox, Error> {
+        self.validate()?;
+        if !radius_m.is_finite() {
+            return Err(Error::InvalidParameter(
+                "Radius must be a finite number of metres.",
+            ));
+        }
+        let d_lat = (radius_m.abs() / EARTH_RADIUS_METRES).to_degrees();
+        let south = (self.lat - d_lat).max(-90.0);
+        let north = (self.lat + d_lat).min(90.0);
+        if south <= -90.0 || north >= 90.0 {
+            return Ok(BoundingBox::new(south, -180.0, north, 180.0));
+        }
+        let d_lng = d_lat / self.lat.to_radians().cos();
+        Ok(BoundingBox::new(
+            south,
+            (self.lng - d_lng).max(-180.0),
+            north,
+            (self.lng + d_lng).min(180.0),
+        ))
+    }
+
+    pub fn round_to(&self, decimals: u32) -> Coordinates {
+        let factor = 10f64.powi(decimals as i32);
+        Coordinates::new(
+            (self.lat * factor).round() / factor,
+            (self.lng * factor).round() / factor,
+        )
+    }
+}
+
+impl Validator for Coordinates {
+    fn validate(&self) -> Result<(), Error> {
+        if !(-90.0..=90.0).contains(&self.lat) {
+            return Err(Error::InvalidParameter(
+                "Latitude must be between -90 and 90.",
+            ));
+        }
+        if !(-180.0..=180.0).contains(&self.lng) {
+            return Err(Error::InvalidParameter(
+                "Longitude must be between -180 and 180.",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Coordinates {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (lat, lng) = s.split_once(',').ok_or(Error::InvalidParameter(
+            "Coordinates must be formatted as \"lat,lng\".",
+        ))?;
+        let parse = |value: &str| {
+            value.trim().parse::<f64>().map_err(|_| {
+                Error::InvalidParameter("Coordinates must be formatted as \"lat,lng\".")
+            })
+        };
+        let coordinates = Coordinates::new(parse(lat)?, parse(lng)?);
+        coordinates.validate()?;
+        Ok(coordinates)
+    }
+}
+
+const MISPLACED_HEMISPHERE: &str =
+    "The latitude takes an N or S hemisphere and the longitude an E or W one.";
+
+/// Removes a leading `lat`, `lng`, `latitude` or `longitude` label and its `:` or `=`.
+fn strip_coordinate_label(part: &str) -> &str {
+    ["latitude", "longitude", "lat", "lng"]
+        .iter()
+        .find_map(|label| part.strip_prefix(label))
+        .unwrap_or(part)
+        .trim_start_matches([':', '='])
+}
+
+/// Splits a leading or trailing hemisphere letter, as in `"n51.5"` or `"51.5°n"`, off a value.
+fn split_hemisphere(part: &str) -> (&str, Option<char>) {
+    let is_hemisphere = |c: char| matches!(c, 'n' | 's' | 'e' | 'w');
+    let (value, hemisphere) = if let Some(value) = part.strip_prefix(is_hemisphere) {
+        (value, part.chars().next())
+    } else if let Some(value) = part.strip_suffix(is_hemisphere) {
+        (value, part.chars().last())
+    } else {
+        (part, None)
+    };
+    (value.trim_end_matches('°'), hemisphere)
+}
+
+/// Parses `value` in degrees, negating it for the `negative` hemisphere of `(positive, negative)`.
+fn signed_degrees(
+    value: &str,
+    hemisphere: Option<char>,
+    (positive, negative): (char, char),
+) -> Result<f64, Error> {
+    let degrees = value
+        .parse::<f64>()
+        .map_err(|_| Error::InvalidParameter("Coordinates must be formatted as \"lat,lng\"."))?;
+    match hemisphere {
+        None => Ok(degrees),
+        Some(_) if degrees.is_sign_negative() => Err(Error::InvalidParameter(
+            "A coordinate with a hemisphere letter must not be negative.",
+        )),
+        Some(hemisphere) if hemisphere == positive => Ok(degrees),
+        Some(hemisphere) if hemisphere == negative => Ok(-degrees),
+        Some(_) => Err(Error::InvalidParameter(MISPLACED_HEMISPHERE)),
+    }
+}
+
+/// `Coordinates` rounded to a fixed number of decimals and stored as scaled integers, so they
+/// can be compared exactly and used as `HashMap`/`HashSet` keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct RoundedCoordinates {
+    lat: i64,
+    lng: i64,
+    decimals: u32,
+}
+
+impl RoundedCoordinates {
+    pub fn new(coordinates: &Coordinates, decimals: u32) -> Self {
+        let factor = 10f64.powi(decimals as i32);
+        let rounded = coordinates.round_to(decimals);
+        Self {
+            lat: (rounded.lat * factor).round() as i64,
+            lng: (rounded.lng * factor).round() as i64,
+            decimals,
+        }
+    }
+
+    pub fn to_coordinates(&self) -> Coordinates {
+        let factor = 10f64.powi(self.decimals as i32);
+        Coordinates::new(self.lat as f64 / factor, self.lng as f64 / factor)
+    }
 }
 
-#[derive(Debug, Clone)]
+impl From<RoundedCoordinates> for Coordinates {
+    fn from(rounded: RoundedCoordinates) -> Self {
+        rounded.to_coordinates()
+    }
+}
+
+/// Reads the tuple as `(lat, lng)`.
+impl From<(f64, f64)> for Coordinates {
+    fn from((lat, lng): (f64, f64)) -> Self {
+        Coordinates::new(lat, lng)
+    }
+}
+
+impl From<Coordinates> for (f64, f64) {
+    fn from(coordinates: Coordinates) -> Self {
+        (coordinates.lat, coordinates.lng)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct Circle {
     lat: f64,
     lng: f64,
-    radius: u32,
+    /// In kilometres, as the API expects.
+    radius: f64,
 }
 
 impl Circle {
+    /// Creates a circle whose `radius` is in kilometres.
     pub fn new(lat: f64, lng: f64, radius: u32) -> Self {
-        Self { lat, lng, radius }
+        Self::from_km(lat, lng, radius.into())
+    }
+
+    pub fn from_km(lat: f64, lng: f64, radius_km: f64) -> Self {
+        Self {
+            lat,
+            lng,
+            radius: radius_km,
+        }
+    }
+
+    pub fn from_meters(lat: f64, lng: f64, radius_meters: f64) -> Self {
+        Self::from_km(lat, lng, radius_meters / 1000.0)
+    }
+
+    pub fn center(&self) -> Coordinates {
+        Coordinates::new(self.lat, self.lng)
+    }
+
+    /// The radius in kilometres.
+    pub fn radius(&self) -> f64 {
+        self.radius
     }
 }
 
 impl fmt::Display for Circle {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{},{},{}", self.lat, self.lng, self.radius)
+        write!(
+            f,
+            "{},{},{}",
+            format_degrees(self.lat, f.precision()),
+            format_degrees(self.lng, f.precision()),
+            self.radius
+        )
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Polygon {
     coordinates: Vec<Coordinates>,
 }
@@ -135,6 +434,26 @@ impl Polygon {
             coordinates: coordinates.to_vec(),
         }
     }
+
+    /// Creates a polygon from its distinct vertices, repeating the first one at the end unless
+    /// it is already closed. The count limits apply to the closed ring, so three vertices make
+    /// a valid four-point polygon.
+    pub fn new_closed(coordinates: &[Coordinates]) -> Self {
+        let mut polygon = Self::new(coordinates);
+        if let Some(first) = coordinates.first() {
+            if coordinates.last() != Some(first) {
+                polygon.coordinates.push(first.clone());
+            }
+        }
+        polygon
+    }
+
+    /// Returns `true` only if every vertex lies inside `bounding_box`.
+    pub fn within(&self, bounding_box: &BoundingBox) -> bool {
+        self.coordinates
+            .iter()
+            .all(|coordinates| bounding_box.contains(coordinates))
+    }
 }
 
 impl Validator for Polygon {
@@ -163,20 +482,42 @@ impl fmt::Display for Polygon {
         let coords = self
             .coordinates
             .iter()
-            .map(ToString::to_string)
+            .map(|coordinates| format_with_precision(coordinates, f.precision()))
             .collect::<Vec<_>>()
             .join(",");
         write!(f, "{coords}")
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Square {
     pub southwest: Coordinates,
     pub northeast: Coordinates,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+impl Square {
+    /// Returns the square as a GeoJSON `Feature` with a closed, counter-clockwise `Polygon`
+    /// ring in `[lng, lat]` order.
+    pub fn to_geojson(&self) -> serde_json::Value {
+        let (sw, ne) = (&self.southwest, &self.northeast);
+        serde_json::json!({
+            "type": "Feature",
+            "properties": {},
+            "geometry": {
+                "type": "Polygon",
+                "coordinates": [[
+                    [sw.lng, sw.lat],
+                    [ne.lng, sw.lat],
+                    [ne.lng, ne.lat],
+                    [sw.lng, ne.lat],
+                    [sw.lng, sw.lat]
+                ]]
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Address {
     pub country: String,
     pub square: Square,
@@ -185,26 +526,83 @@ pub struct Address {
     pub coordinates: Coordinates,
     pub words: String,
     pub language: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub locale: Option<String>,
     pub map: String,
 }
 
+impl Address {
+    /// Serializes the address as minified, single-line JSON, e.g. for log aggregation.
+    pub fn to_json_line(&self) -> String {
+        serde_json::to_string(self).expect("an Address always serializes to JSON")
+    }
+}
+
+/// Formats as `words (lat, lng) — nearest place, country`, for log messages.
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}, {}) — {}, {}",
+            self.words,
+            self.coordinates.lat,
+            self.coordinates.lng,
+            self.nearest_place,
+            self.country
+        )
+    }
+}
+
 impl FormattedAddress for Address {
     fn format() -> &'static str {
         "json"
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+impl From<&Address> for Coordinates {
+    fn from(address: &Address) -> Self {
+        address.coordinates.clone()
+    }
+}
+
+impl From<&Address> for ConvertToCoordinates {
+    fn from(address: &Address) -> Self {
+        ConvertToCoordinates::new(&address.words)
+    }
+}
+
+impl From<&Suggestion> for ConvertToCoordinates {
+    fn from(suggestion: &Suggestion) -> Self {
+        ConvertToCoordinates::new(&suggestion.words)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Geometry {
     pub coordinates: Vec<f64>,
     #[serde(rename = "type")]
     pub kind: String,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// The `properties` of an `AddressGeoJson` feature. Fields the wrapper doesn't know about yet
+/// are kept, untouched, in `extra`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AddressProperties {
+    pub country: String,
+    #[serde(rename = "nearestPlace")]
+    pub nearest_place: String,
+    pub words: String,
+    pub language: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    pub map: String,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AddressGeoJson {
-    pub features: Vec<Feature<Geometry>>,
+    pub features: Vec<Feature<Geometry, AddressProperties>>,
     #[serde(rename = "type")]
     pub kind: String,
 }
@@ -215,6 +613,68 @@ impl FormattedAddress for AddressGeoJson {
     }
 }
 
+// `geo` uses x/y order, so longitude maps to x and latitude to y.
+#[cfg(feature = "geo")]
+impl From<Coordinates> for geo::Point<f64> {
+    fn from(coordinates: Coordinates) -> Self {
+        geo::Point::new(coordinates.lng, coordinates.lat)
+    }
+}
+
+#[cfg(feature = "geo")]
+impl From<geo::Point<f64>> for Coordinates {
+    fn from(point: geo::Point<f64>) -> Self {
+        Coordinates::new(point.y(), point.x())
+    }
+}
+
+#[cfg(feature = "geo")]
+impl From<Square> for geo::Rect<f64> {
+    fn from(square: Square) -> Self {
+        geo::Rect::new(
+            geo::coord! { x: square.southwest.lng, y: square.southwest.lat },
+            geo::coord! { x: square.northeast.lng, y: square.northeast.lat },
+        )
+    }
+}
+
+#[cfg(feature = "geo")]
+impl From<geo::Rect<f64>> for Square {
+    fn from(rect: geo::Rect<f64>) -> Self {
+        Square {
+            southwest: geo::Point::from(rect.min()).into(),
+            northeast: geo::Point::from(rect.max()).into(),
+        }
+    }
+}
+
+#[cfg(feature = "geo")]
+impl From<Polygon> for geo::Polygon<f64> {
+    fn from(polygon: Polygon) -> Self {
+        let exterior = polygon
+            .coordinates
+            .into_iter()
+            .map(|coordinates| geo::coord! { x: coordinates.lng, y: coordinates.lat })
+            .collect::<Vec<_>>();
+        geo::Polygon::new(exterior.into(), vec![])
+    }
+}
+
+/// Only the exterior ring is kept, since the API has no notion of holes.
+#[cfg(feature = "geo")]
+impl From<geo::Polygon<f64>> for Polygon {
+    fn from(polygon: geo::Polygon<f64>) -> Self {
+        let (exterior, _) = polygon.into_inner();
+        Polygon {
+            coordinates: exterior
+                .into_points()
+                .into_iter()
+                .map(Coordinates::from)
+                .collect(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod location_tests {
     use super::*;
@@ -252,6 +712,258 @@ mod location_tests {
         }
     }
 
+    #[test]
+    fn test_coordinates_display_precision() {
+        let coordinates = Coordinates::new(51.521251, -0.203586);
+        assert_eq!(format!("{:.3}", coordinates), "51.521,-0.204");
+        assert_eq!(format!("{:.6}", Coordinates::new(51.5, -0.1)), "51.5,-0.1");
+    }
+
+    #[test]
+    fn test_convert_to_3wa_to_hash_map_with_precision() {
+        let convert = ConvertTo3wa::new(51.521251, -0.203586);
+        let map = convert.to_hash_map_with_precision(Some(2)).unwrap();
+        assert_eq!(map.get("coordinates"), Some(&"51.52,-0.2".to_string()));
+    }
+
+    #[test]
+    fn test_circle_and_polygon_display_precision() {
+        let circle = Circle::new(51.521251, -0.203586, 10);
+        assert_eq!(format!("{:.4}", circle), "51.5213,-0.2036,10");
+
+        let polygon = Polygon::new(&[
+            Coordinates::new(51.521251, -0.203586),
+            Coordinates::new(51.521261, -0.203575),
+        ]);
+        assert_eq!(format!("{:.4}", polygon), "51.5213,-0.2036,51.5213,-0.2036");
+    }
+
+    #[test]
+    fn test_coordinates_round_to() {
+        let coordinates = Coordinates::new(51.521251, -0.203586);
+        assert_eq!(coordinates.round_to(3), Coordinates::new(51.521, -0.204));
+    }
+
+    #[test]
+    fn test_rounded_coordinates_as_map_key() {
+        let mut counts = std::collections::HashMap::new();
+        for coordinates in [
+            Coordinates::new(51.5212501, -0.2035861),
+            Coordinates::new(51.5212498, -0.2035858),
+            Coordinates::new(51.520847, -0.195521),
+        ] {
+            *counts
+                .entry(RoundedCoordinates::new(&coordinates, 5))
+                .or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 2);
+        let key = RoundedCoordinates::new(&Coordinates::new(51.52125, -0.20359), 5);
+        assert_eq!(counts.get(&key), Some(&2));
+        assert_eq!(key.to_coordinates(), Coordinates::new(51.52125, -0.20359));
+    }
+
+    #[test]
+    fn test_polygon_within() {
+        let bounding_box = BoundingBox::new(51.0, -1.0, 52.0, 1.0);
+        let inside = Polygon::new(&[
+            Coordinates::new(51.2, -0.5),
+            Coordinates::new(51.8, -0.5),
+            Coordinates::new(51.8, 0.5),
+            Coordinates::new(51.2, -0.5),
+        ]);
+        assert!(inside.within(&bounding_box));
+
+        let straddling = Polygon::new(&[
+            Coordinates::new(51.2, -0.5),
+            Coordinates::new(52.5, -0.5),
+            Coordinates::new(51.8, 0.5),
+            Coordinates::new(51.2, -0.5),
+        ]);
+        assert!(!straddling.within(&bounding_box));
+    }
+
+    #[test]
+    fn test_coordinates_from_str() {
+        assert_eq!(
+            "51.521251,-0.203586".parse::<Coordinates>().unwrap(),
+            Coordinates::new(51.521251, -0.203586)
+        );
+        let coordinates = Coordinates::new(51.521251, -0.203586);
+        assert_eq!(
+            coordinates.to_string().parse::<Coordinates>().unwrap(),
+            coordinates
+        );
+        assert_eq!(
+            " -33.8688 , 151.2093 ".parse::<Coordinates>().unwrap(),
+            Coordinates::new(-33.8688, 151.2093)
+        );
+
+        assert!("51.521251".parse::<Coordinates>().is_err());
+        assert!("51.521251,west".parse::<Coordinates>().is_err());
+        assert!("51.521251,-0.203586,1".parse::<Coordinates>().is_err());
+        assert!("91,0".parse::<Coordinates>().is_err());
+        assert!("0,-180.5".parse::<Coordinates>().is_err());
+    }
+
+    #[test]
+    fn test_convert_to_coordinates_rejects_coordinates() {
+        assert_eq!(
+            ConvertToCoordinates::new("51.5,-0.2").to_hash_map(),
+            Err(Error::InvalidParameter(
+                "The words look like coordinates; use convert_to_3wa to convert coordinates."
+            ))
+        );
+        assert!(ConvertToCoordinates::new("filled.count.soap")
+            .to_hash_map()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_polygon_new_closed() {
+        let vertices = [
+            Coordinates::new(51.521, -0.343),
+            Coordinates::new(52.6, 2.3324),
+            Coordinates::new(54.234, 8.343),
+        ];
+        let polygon = Polygon::new_closed(&vertices);
+        assert!(polygon.validate().is_ok());
+        assert_eq!(
+            polygon.to_string(),
+            "51.521,-0.343,52.6,2.3324,54.234,8.343,51.521,-0.343"
+        );
+
+        let mut closed = vertices.to_vec();
+        closed.push(vertices[0].clone());
+        assert_eq!(Polygon::new_closed(&closed).coordinates, closed);
+        assert!(Polygon::new_closed(&vertices[..2]).validate().is_err());
+    }
+
+    #[test]
+    fn test_coordinates_try_new() {
+        for (lat, lng) in [(-90.0, -180.0), (90.0, 180.0), (0.0, 0.0)] {
+            assert!(Coordinates::try_new(lat, lng).is_ok());
+            assert!(Coordinates::new(lat, lng).is_valid());
+        }
+        assert_eq!(
+            Coordinates::try_new(90.000001, 0.0),
+            Err(Error::InvalidParameter(
+                "Latitude must be between -90 and 90."
+            ))
+        );
+        assert_eq!(
+            Coordinates::try_new(0.0, -180.000001),
+            Err(Error::InvalidParameter(
+                "Longitude must be between -180 and 180."
+            ))
+        );
+        assert!(!Coordinates::new(9999.0, -9999.0).is_valid());
+        assert!(!Coordinates::new(f64::NAN, 0.0).is_valid());
+    }
+
+    #[test]
+    fn test_coordinates_from_tuple() {
+        let coordinates: Coordinates = (51.521251, -0.203586).into();
+        assert_eq!(coordinates, Coordinates::new(51.521251, -0.203586));
+        let (lat, lng): (f64, f64) = coordinates.into();
+        assert_eq!((lat, lng), (51.521251, -0.203586));
+    }
+
+    #[test]
+    fn test_coordinates_parse_flexible() {
+        let expected = Coordinates::new(51.521251, -0.203586);
+        assert_eq!(
+            Coordinates::parse_flexible("51.521251,-0.203586").unwrap(),
+            expected
+        );
+        assert_eq!(
+            Coordinates::parse_flexible("51.521251, -0.203586").unwrap(),
+            expected
+        );
+        assert_eq!(
+            Coordinates::parse_flexible("51.521251 -0.203586").unwrap(),
+            expected
+        );
+        assert_eq!(
+            Coordinates::parse_flexible("lat: 51.521251, lng: -0.203586").unwrap(),
+            expected
+        );
+        assert_eq!(
+            Coordinates::parse_flexible("latitude=51.521251 longitude=-0.203586").unwrap(),
+            expected
+        );
+        assert!(Coordinates::parse_flexible("lat: 51.521251").is_err());
+        assert!(Coordinates::parse_flexible("lat: north, lng: west").is_err());
+        assert!(Coordinates::parse_flexible("foo51.521251, bar-0.203586").is_err());
+    }
+
+    #[test]
+    fn test_coordinates_parse_flexible_hemispheres() {
+        let expected = Coordinates::new(51.521251, -0.203586);
+        for input in [
+            "N51.521251 W0.203586",
+            "51.521251N, 0.203586W",
+            "51.521251°N 0.203586°W",
+            "51.521251 N, 0.203586 W",
+            "N 51.521251, W 0.203586",
+            "lat: N51.521251, lng: W0.203586",
+        ] {
+            assert_eq!(
+                Coordinates::parse_flexible(input),
+                Ok(expected.clone()),
+                "{input}"
+            );
+        }
+        assert_eq!(
+            Coordinates::parse_flexible("S33.8688 E151.2093"),
+            Ok(Coordinates::new(-33.8688, 151.2093))
+        );
+
+        // Swapped, repeated or contradicting hemispheres are rejected rather than guessed
+        assert!(Coordinates::parse_flexible("W0.203586 N51.521251").is_err());
+        assert!(Coordinates::parse_flexible("N51.521251 N0.203586").is_err());
+        assert!(Coordinates::parse_flexible("N 51.521251 S, W0.203586").is_err());
+        assert!(Coordinates::parse_flexible("51.521251 W-0.203586").is_err());
+        assert!(Coordinates::parse_flexible("51.521251 -0.203586 W").is_err());
+    }
+
+    #[test]
+    fn test_address_to_json_line() {
+        let address: Address = serde_json::from_str(
+            r#"{
+                "country": "GB",
+                "square": {
+                    "southwest": { "lng": -0.203607, "lat": 51.521241 },
+                    "northeast": { "lng": -0.203575, "lat": 51.521261 }
+                },
+                "nearestPlace": "Bayswater, London",
+                "coordinates": { "lng": -0.203586, "lat": 51.521251 },
+                "words": "filled.count.soap",
+                "language": "en",
+                "map": "https://w3w.co/filled.count.soap"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            address.to_json_line(),
+            r#"{"country":"GB","square":{"southwest":{"lat":51.521241,"lng":-0.203607},"northeast":{"lat":51.521261,"lng":-0.203575}},"nearestPlace":"Bayswater, London","coordinates":{"lat":51.521251,"lng":-0.203586},"words":"filled.count.soap","language":"en","map":"https://w3w.co/filled.count.soap"}"#
+        );
+    }
+
+    #[test]
+    fn test_square_to_geojson() {
+        let square = Square {
+            southwest: Coordinates::new(51.521241, -0.203607),
+            northeast: Coordinates::new(51.521261, -0.203575),
+        };
+        let geojson = square.to_geojson();
+        assert_eq!(geojson["type"], "Feature");
+        assert_eq!(geojson["geometry"]["type"], "Polygon");
+        let ring = geojson["geometry"]["coordinates"][0].as_array().unwrap();
+        assert_eq!(ring.len(), 5);
+        assert_eq!(ring.first(), ring.last());
+        assert_eq!(ring[0], serde_json::json!([-0.203607, 51.521241]));
+    }
+
     #[test]
     fn test_convert_to_coordinates_new() {
         let convert = ConvertToCoordinates::new("index.home.raft");
@@ -265,4 +977,270 @@ mod location_tests {
         assert_eq!(convert.words, Some("index.home.raft".to_string()));
         assert_eq!(convert.locale, Some("en".to_string()));
     }
+
+    #[test]
+    fn test_address_geojson_serialize_round_trip() {
+        let payload = serde_json::json!({
+            "type": "FeatureCollection",
+            "features": [{
+                "type": "Feature",
+                "bbox": [-0.203607, 51.521241, -0.203575, 51.521261],
+                "geometry": { "type": "Point", "coordinates": [-0.203586, 51.521251] },
+                "properties": {
+                    "country": "GB",
+                    "nearestPlace": "Bayswater, London",
+                    "words": "filled.count.soap",
+                    "language": "en",
+                    "map": "https://w3w.co/filled.count.soap"
+                }
+            }]
+        });
+        let geojson: AddressGeoJson = serde_json::from_value(payload.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&geojson).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_coordinates_distance_to() {
+        let london = Coordinates::new(51.5074, -0.1278);
+        let paris = Coordinates::new(48.8566, 2.3522);
+        let distance = london.distance_to(&paris);
+        assert!((distance - 343_500.0).abs() < 1_000.0, "{distance}");
+        assert_eq!(distance, paris.distance_to(&london));
+        assert_eq!(london.distance_to(&london), 0.0);
+
+        let antipode = Coordinates::new(-51.5074, 179.8722);
+        let distance = london.distance_to(&antipode);
+        assert!((distance - 20_015_000.0).abs() < 1_000.0, "{distance}");
+    }
+
+    #[test]
+    fn test_coordinates_bearing_to() {
+        let origin = Coordinates::new(0.0, 0.0);
+        assert!((origin.bearing_to(&Coordinates::new(1.0, 0.0)) - 0.0).abs() < 1e-9);
+        assert!((origin.bearing_to(&Coordinates::new(0.0, 1.0)) - 90.0).abs() < 1e-9);
+        assert!((origin.bearing_to(&Coordinates::new(-1.0, 0.0)) - 180.0).abs() < 1e-9);
+        assert!((origin.bearing_to(&Coordinates::new(0.0, -1.0)) - 270.0).abs() < 1e-9);
+
+        let london = Coordinates::new(51.5074, -0.1278);
+        let paris = Coordinates::new(48.8566, 2.3522);
+        assert!((london.bearing_to(&paris) - 148.1).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_address_serialize_round_trip() {
+        let payload = serde_json::json!({
+            "country": "GB",
+            "square": {
+                "southwest": { "lng": -0.203607, "lat": 51.521241 },
+                "northeast": { "lng": -0.203575, "lat": 51.521261 }
+            },
+            "nearestPlace": "Bayswater, London",
+            "coordinates": { "lng": -0.203586, "lat": 51.521251 },
+            "words": "filled.count.soap",
+            "language": "en",
+            "locale": "en_gb",
+            "map": "https://w3w.co/filled.count.soap"
+        });
+        let address: Address = serde_json::from_value(payload.clone()).unwrap();
+        let json = serde_json::to_string(&address).unwrap();
+        let round_tripped: Address = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_value(&round_tripped).unwrap(), payload);
+        assert_eq!(round_tripped.coordinates, address.coordinates);
+    }
+
+    #[test]
+    fn test_coordinates_bounding_box() {
+        let london = Coordinates::new(51.521251, -0.203586);
+        let small = london.bounding_box(500.0).unwrap();
+        let large = london.bounding_box(1000.0).unwrap();
+        assert!(small.contains(&london));
+        assert_eq!(small.center().round_to(6), london);
+
+        let [south, west, north, east] = corners(&small);
+        let [large_south, large_west, large_north, large_east] = corners(&large);
+        assert!(((large_north - large_south) / (north - south) - 2.0).abs() < 1e-9);
+        assert!(((large_east - large_west) / (east - west) - 2.0).abs() < 1e-9);
+        let height =
+            Coordinates::new(south, london.lng).distance_to(&Coordinates::new(north, london.lng));
+        assert!((height - 1000.0).abs() < 1e-6);
+        let width =
+            Coordinates::new(london.lat, west).distance_to(&Coordinates::new(london.lat, east));
+        assert!((width - 1000.0).abs() < 1.0);
+
+        let pole = Coordinates::new(89.999, 10.0).bounding_box(1000.0).unwrap();
+        assert_eq!(corners(&pole)[1..], [-180.0, 90.0, 180.0]);
+        let south_pole = Coordinates::new(-90.0, 0.0).bounding_box(0.0).unwrap();
+        assert_eq!(corners(&south_pole), [-90.0, -180.0, -90.0, 180.0]);
+        let edge = Coordinates::new(0.0, 179.9999)
+            .bounding_box(1000.0)
+            .unwrap();
+        assert_eq!(corners(&edge)[3], 180.0);
+
+        let point = london.bounding_box(0.0).unwrap();
+        assert_eq!(
+            corners(&point),
+            [london.lat, london.lng, london.lat, london.lng]
+        );
+        assert_eq!(
+            corners(&london.bounding_box(-500.0).unwrap()),
+            corners(&small)
+        );
+
+        assert!(london.bounding_box(f64::NAN).is_err());
+        assert!(london.bounding_box(f64::INFINITY).is_err());
+        assert!(Coordinates::new(91.0, 0.0).bounding_box(1000.0).is_err());
+    }
+
+    fn corners(bounding_box: &BoundingBox) -> [f64; 4] {
+        let (southwest, northeast) = (bounding_box.southwest(), bounding_box.northeast());
+        [southwest.lat, southwest.lng, northeast.lat, northeast.lng]
+    }
+
+    #[test]
+    fn test_address_display() {
+        let address: Address = serde_json::from_value(serde_json::json!({
+            "country": "GB",
+            "square": {
+                "southwest": { "lng": -0.203607, "lat": 51.521241 },
+                "northeast": { "lng": -0.203575, "lat": 51.521261 }
+            },
+            "nearestPlace": "Bayswater, London",
+            "coordinates": { "lng": -0.203586, "lat": 51.521251 },
+            "words": "filled.count.soap",
+            "language": "en",
+            "map": "https://w3w.co/filled.count.soap"
+        }))
+        .unwrap();
+        assert_eq!(
+            address.to_string(),
+            "filled.count.soap (51.521251, -0.203586) — Bayswater, London, GB"
+        );
+    }
+
+    #[test]
+    fn test_convert_to_coordinates_strip_slashes() {
+        let words = |request: ConvertToCoordinates| request.to_hash_map().unwrap()["words"].clone();
+        assert_eq!(
+            words(ConvertToCoordinates::new("///filled.count.soap").strip_slashes()),
+            "filled.count.soap"
+        );
+        assert_eq!(
+            words(ConvertToCoordinates::new("filled.count.soap").strip_slashes()),
+            "filled.count.soap"
+        );
+        assert_eq!(
+            words(ConvertToCoordinates::new("///filled.count.soap")),
+            "///filled.count.soap"
+        );
+    }
+
+    #[test]
+    fn test_conversions_from_address_and_suggestion() {
+        let address: Address = serde_json::from_value(serde_json::json!({
+            "country": "GB",
+            "square": {
+                "southwest": { "lng": -0.203607, "lat": 51.521241 },
+                "northeast": { "lng": -0.203575, "lat": 51.521261 }
+            },
+            "nearestPlace": "Bayswater, London",
+            "coordinates": { "lng": -0.203586, "lat": 51.521251 },
+            "words": "filled.count.soap",
+            "language": "en",
+            "map": "https://w3w.co/filled.count.soap"
+        }))
+        .unwrap();
+        assert_eq!(
+            Coordinates::from(&address),
+            Coordinates::new(51.521251, -0.203586)
+        );
+        let words = |request: ConvertToCoordinates| request.to_hash_map().unwrap()["words"].clone();
+        assert_eq!(
+            words(ConvertToCoordinates::from(&address)),
+            "filled.count.soap"
+        );
+
+        let suggestion: Suggestion = serde_json::from_value(serde_json::json!({
+            "country": "GB",
+            "nearestPlace": "Bayswater, London",
+            "words": "index.home.raft",
+            "rank": 1,
+            "language": "en"
+        }))
+        .unwrap();
+        assert_eq!(words((&suggestion).into()), "index.home.raft");
+    }
+
+    #[test]
+    fn test_circle_accessors() {
+        let circle = Circle::new(51.521251, -0.203586, 10);
+        assert_eq!(circle.center(), Coordinates::new(51.521251, -0.203586));
+        assert_eq!(circle.radius(), 10.0);
+        assert_eq!(Circle::from_meters(0.0, 0.0, 500.0).radius(), 0.5);
+    }
+
+    #[cfg(feature = "geo")]
+    #[test]
+    fn test_geo_conversions() {
+        let point: geo::Point<f64> = Coordinates::new(51.521251, -0.203586).into();
+        assert_eq!(point.x(), -0.203586);
+        assert_eq!(point.y(), 51.521251);
+        assert_eq!(
+            Coordinates::from(point),
+            Coordinates::new(51.521251, -0.203586)
+        );
+
+        let rect: geo::Rect<f64> = Square {
+            southwest: Coordinates::new(51.52, -0.2),
+            northeast: Coordinates::new(51.53, -0.19),
+        }
+        .into();
+        assert_eq!(rect.min(), geo::coord! { x: -0.2, y: 51.52 });
+        assert_eq!(rect.max(), geo::coord! { x: -0.19, y: 51.53 });
+        let square = Square::from(rect);
+        assert_eq!(square.southwest, Coordinates::new(51.52, -0.2));
+        assert_eq!(square.northeast, Coordinates::new(51.53, -0.19));
+
+        let polygon = Polygon::new_closed(&[
+            Coordinates::new(51.52, -0.2),
+            Coordinates::new(51.52, -0.19),
+            Coordinates::new(51.53, -0.19),
+        ]);
+        let geo_polygon: geo::Polygon<f64> = polygon.clone().into();
+        assert_eq!(geo_polygon.exterior().0.len(), 4);
+        assert_eq!(
+            geo_polygon.exterior().0[1],
+            geo::coord! { x: -0.19, y: 51.52 }
+        );
+        assert!(geo_polygon.interiors().is_empty());
+        assert_eq!(Polygon::from(geo_polygon).to_string(), polygon.to_string());
+    }
+
+    #[test]
+    fn test_address_geojson_properties() {
+        let geojson: AddressGeoJson = serde_json::from_value(serde_json::json!({
+            "type": "FeatureCollection",
+            "features": [{
+                "type": "Feature",
+                "geometry": { "type": "Point", "coordinates": [-0.203586, 51.521251] },
+                "properties": {
+                    "country": "GB",
+                    "nearestPlace": "Bayswater, London",
+                    "words": "filled.count.soap",
+                    "language": "en",
+                    "map": "https://w3w.co/filled.count.soap",
+                    "newField": [1, 2]
+                }
+            }]
+        }))
+        .unwrap();
+        let properties = &geojson.features[0].properties;
+        assert_eq!(properties.country, "GB");
+        assert_eq!(properties.nearest_place, "Bayswater, London");
+        assert_eq!(properties.words, "filled.count.soap");
+        assert_eq!(properties.language, "en");
+        assert_eq!(properties.locale, None);
+        assert_eq!(properties.map, "https://w3w.co/filled.count.soap");
+        assert_eq!(properties.extra.len(), 1);
+        assert_eq!(properties.extra["newField"], serde_json::json!([1, 2]));
+    }
 }
@@ -1,4 +1,6 @@
 pub mod autosuggest;
+pub mod country;
+pub mod empty;
 pub mod error;
 pub mod feature;
 pub mod gridsection;